@@ -0,0 +1,187 @@
+use crate::grove_buf::GroveBuf;
+use crate::node::Width;
+use crate::shape::Shape;
+use crate::shape::ShapeMismatch;
+use crate::split::SplitGrove;
+use std::any::Any;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
+/// A sequence of trees holding a single [`Shape`] along with any number of
+/// value columns, each of which holds one value per node. Columns are keyed by
+/// their value type, so distinct attributes of the same underlying type should
+/// be wrapped in distinct types. Each column can be added, replaced or removed
+/// independently, without copying the structure, and borrowed as a
+/// [`SplitGrove`] for traversal.
+///
+/// # Example
+/// ```
+/// # use grove::*;
+/// struct Depth(usize);
+///
+/// let mut g = AttributeGrove::from(grove_buf![['a', 'b'] => 'c', 'd']);
+/// let depths = g.column::<char>().unwrap().scan_down(|_, p, _| {
+///   Depth(p.map_or(0, |Depth(d)| d + 1))
+/// });
+/// g.insert_column(depths.into_values()).unwrap();
+///
+/// let chars: Vec<_> = g.column::<char>().unwrap().nodes(Preorder).collect();
+/// assert_eq!(chars, vec![&'a', &'b', &'c', &'d']);
+/// let depths: Vec<_> = g
+///   .column::<Depth>()
+///   .unwrap()
+///   .nodes(Preorder)
+///   .map(|Depth(d)| *d)
+///   .collect();
+/// assert_eq!(depths, vec![1, 1, 0, 0]);
+/// ```
+pub struct AttributeGrove<W = usize> {
+  shape: Shape<W>,
+  columns: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl<W: Width> AttributeGrove<W> {
+  /// Constructs an [`AttributeGrove`] with the given shape and no columns.
+  pub fn new(shape: Shape<W>) -> AttributeGrove<W> {
+    AttributeGrove {
+      shape,
+      columns: HashMap::new(),
+    }
+  }
+
+  /// Returns the [`Shape`] shared by all columns.
+  pub fn shape(&self) -> &Shape<W> {
+    &self.shape
+  }
+
+  /// Returns `true` if and only if the [`AttributeGrove`] contains no trees.
+  pub fn is_empty(&self) -> bool {
+    self.shape.is_empty()
+  }
+
+  /// Returns the number of nodes in the [`AttributeGrove`].
+  pub fn len(&self) -> usize {
+    self.shape.len()
+  }
+
+  /// Returns `true` if and only if a column of values of type `C` is present.
+  pub fn contains_column<C: 'static>(&self) -> bool {
+    self.columns.contains_key(&TypeId::of::<C>())
+  }
+
+  /// Adds a column holding `values` (in [`Preorder`][crate::Preorder]),
+  /// returning the values of the column of the same type that it replaces, if
+  /// any. Returns [`ShapeMismatch`] if there is not exactly one value per node.
+  pub fn insert_column<C: 'static>(
+    &mut self,
+    values: Vec<C>,
+  ) -> Result<Option<Vec<C>>, ShapeMismatch> {
+    if values.len() != self.shape.len() {
+      return Err(ShapeMismatch);
+    }
+    Ok(
+      self
+        .columns
+        .insert(TypeId::of::<C>(), Box::new(values))
+        .map(|column| *column.downcast().unwrap()),
+    )
+  }
+
+  /// Removes the column of values of type `C`, returning its values in
+  /// [`Preorder`][crate::Preorder], if present.
+  pub fn remove_column<C: 'static>(&mut self) -> Option<Vec<C>> {
+    self
+      .columns
+      .remove(&TypeId::of::<C>())
+      .map(|column| *column.downcast().unwrap())
+  }
+
+  /// Returns a [`SplitGrove`] view of the column of values of type `C`, if
+  /// present.
+  pub fn column<C: 'static>(&self) -> Option<SplitGrove<'_, C, W>> {
+    let values = self.columns.get(&TypeId::of::<C>())?;
+    Some(SplitGrove {
+      widths: &self.shape.widths,
+      values: values.downcast_ref::<Vec<C>>().unwrap(),
+    })
+  }
+
+  /// Returns the values of the column of type `C` in
+  /// [`Preorder`][crate::Preorder], if present.
+  pub fn column_mut<C: 'static>(&mut self) -> Option<&mut [C]> {
+    let values = self.columns.get_mut(&TypeId::of::<C>())?;
+    Some(values.downcast_mut::<Vec<C>>().unwrap())
+  }
+}
+
+/// Constructs an [`AttributeGrove`] with the shape of the given [`GroveBuf`]
+/// and a single column holding its values.
+impl<C: 'static, W: Width> From<GroveBuf<C, W>> for AttributeGrove<W> {
+  fn from(g: GroveBuf<C, W>) -> AttributeGrove<W> {
+    let (shape, values) = g.into_parts();
+    let mut result = AttributeGrove::new(shape);
+    result.insert_column(values).unwrap();
+    result
+  }
+}
+
+impl<W: Debug> Debug for AttributeGrove<W> {
+  fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    f.debug_struct("AttributeGrove")
+      .field("shape", &self.shape)
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grove_buf;
+  use crate::traversal::Preorder;
+
+  #[test]
+  fn columns() {
+    let mut g = AttributeGrove::from(grove_buf![[1, 2] => 3, 4]);
+    assert_eq!(g.len(), 4);
+    assert!(g.contains_column::<i32>());
+    assert!(!g.contains_column::<&str>());
+    assert_eq!(g.insert_column(vec!["a", "b", "c"]), Err(ShapeMismatch));
+    assert_eq!(g.insert_column(vec!["a", "b", "c", "d"]), Ok(None));
+    assert_eq!(
+      g.insert_column(vec!["w", "x", "y", "z"]),
+      Ok(Some(vec!["a", "b", "c", "d"]))
+    );
+
+    let strs = g.column::<&str>().unwrap();
+    let v: Vec<_> = strs.tree(2).children_rev().collect();
+    assert_eq!(v.len(), 2);
+    assert_eq!(*v[0].root(), "x");
+
+    for n in g.column_mut::<i32>().unwrap() {
+      *n *= 10;
+    }
+    let v: Vec<_> = g.column::<i32>().unwrap().nodes(Preorder).collect();
+    assert_eq!(v, vec![&10, &20, &30, &40]);
+
+    assert_eq!(g.remove_column::<i32>(), Some(vec![10, 20, 30, 40]));
+    assert_eq!(g.remove_column::<i32>(), None);
+    assert!(g.column::<i32>().is_none());
+    assert!(g.column::<&str>().is_some());
+  }
+
+  #[test]
+  fn scanned_column() {
+    #[derive(Debug, PartialEq)]
+    struct Sum(i32);
+
+    let mut g = AttributeGrove::from(grove_buf![[1, 2] => 3, 4]);
+    let sums = g.column::<i32>().unwrap().scan_up(|n, children| {
+      Sum(n + children.iter().map(|Sum(s)| s).sum::<i32>())
+    });
+    assert_eq!(g.insert_column(sums.into_values()), Ok(None));
+    let v: Vec<_> = g.column::<Sum>().unwrap().nodes(Preorder).collect();
+    assert_eq!(v, vec![&Sum(1), &Sum(2), &Sum(6), &Sum(4)]);
+  }
+}
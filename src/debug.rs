@@ -0,0 +1,194 @@
+use crate::grove::Grove;
+use crate::grove_buf::GroveBuf;
+use crate::node::Node;
+use crate::node::Width;
+use crate::tree::Tree;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result;
+use std::fmt::Write;
+
+// Trees are written with the syntax accepted by `grove_buf!`, so that
+// `[[1, 2] => 3, 4]` is written for `grove_buf![[1, 2] => 3, 4]`. The
+// alternate form places each child on its own line, indented by its depth.
+// Nodes are written iteratively so that arbitrarily deep trees can be
+// formatted.
+
+enum Step {
+  // Writes the tree rooted at the given index, at the given depth. The flag
+  // indicates whether it is the first in its list.
+  Enter(usize, usize, bool),
+  // Closes the list of children of the tree rooted at the given index.
+  Leave(usize, usize),
+}
+
+// Schedules each tree in `nodes[start..end]` to be written at `depth`, such
+// that the leftmost tree is at the top of `stack`.
+fn schedule<T, W: Width>(
+  nodes: &[Node<T, W>],
+  start: usize,
+  mut end: usize,
+  depth: usize,
+  stack: &mut Vec<Step>,
+) {
+  let base = stack.len();
+  while end > start {
+    stack.push(Step::Enter(end - 1, depth, false));
+    end -= nodes[end - 1].width();
+  }
+  if stack.len() > base {
+    if let Some(Step::Enter(_, _, first)) = stack.last_mut() {
+      *first = true;
+    }
+  }
+}
+
+fn indent(f: &mut Formatter, depth: usize) -> Result {
+  f.write_char('\n')?;
+  for _ in 0..depth {
+    f.write_str("    ")?;
+  }
+  Ok(())
+}
+
+fn write_value<T: Debug>(f: &mut Formatter, value: &T, depth: usize) -> Result {
+  if !f.alternate() {
+    return write!(f, "{:?}", value);
+  }
+  for (i, line) in format!("{:#?}", value).split('\n').enumerate() {
+    if i > 0 {
+      indent(f, depth)?;
+    }
+    f.write_str(line)?;
+  }
+  Ok(())
+}
+
+// Writes the trees in `nodes` as a list, or `nodes` as a single tree if `list`
+// is `false`.
+fn write_trees<T: Debug, W: Width>(
+  f: &mut Formatter,
+  nodes: &[Node<T, W>],
+  list: bool,
+) -> Result {
+  let mut stack = vec![];
+  if list {
+    f.write_char('[')?;
+    schedule(nodes, 0, nodes.len(), 1, &mut stack);
+  } else {
+    stack.push(Step::Enter(nodes.len() - 1, 0, true));
+  }
+  let nonempty = !stack.is_empty();
+  while let Some(step) = stack.pop() {
+    let depth = match step {
+      Step::Enter(root, depth, first) => {
+        if depth > 0 {
+          if f.alternate() {
+            indent(f, depth)?;
+          } else if !first {
+            f.write_str(", ")?;
+          }
+        }
+        let node = &nodes[root];
+        if node.width() > 1 {
+          f.write_char('[')?;
+          stack.push(Step::Leave(root, depth));
+          schedule(nodes, root + 1 - node.width(), root, depth + 1, &mut stack);
+          continue;
+        }
+        write_value(f, &node.value, depth)?;
+        depth
+      }
+      Step::Leave(root, depth) => {
+        if f.alternate() {
+          indent(f, depth)?;
+        }
+        f.write_str("] => ")?;
+        write_value(f, &nodes[root].value, depth)?;
+        depth
+      }
+    };
+    if depth > 0 && f.alternate() {
+      f.write_char(',')?;
+    }
+  }
+  if list {
+    if nonempty && f.alternate() {
+      indent(f, 0)?;
+    }
+    f.write_char(']')?;
+  }
+  Ok(())
+}
+
+/// Writes the tree with the syntax accepted by
+/// [`grove_buf!`][crate::grove_buf], such as `[1, 2] => 3`.
+impl<T: Debug, W: Width> Debug for Tree<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    write_trees(f, &self.nodes, false)
+  }
+}
+
+/// Writes the trees as a list with the syntax accepted by
+/// [`grove_buf!`][crate::grove_buf], such as `[[1, 2] => 3, 4]`.
+impl<T: Debug, W: Width> Debug for Grove<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    write_trees(f, &self.nodes, true)
+  }
+}
+
+/// Writes the trees as a list with the syntax accepted by
+/// [`grove_buf!`][crate::grove_buf], such as `[[1, 2] => 3, 4]`.
+impl<T: Debug, W: Width> Debug for GroveBuf<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    write_trees(f, &self.nodes, true)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::grove_buf;
+  use crate::grove_buf::GroveBuf;
+
+  #[test]
+  fn compact() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    assert_eq!(format!("{:?}", g), "[[1, [2] => 3] => 4, 5]");
+    assert_eq!(format!("{:?}", g.as_ref()), "[[1, [2] => 3] => 4, 5]");
+    assert_eq!(format!("{:?}", &g[3]), "[1, [2] => 3] => 4");
+    assert_eq!(format!("{:?}", &g[4]), "5");
+    assert_eq!(format!("{:?}", GroveBuf::<i32>::new()), "[]");
+    assert_eq!(format!("{:?}", grove_buf!["a"]), "[\"a\"]");
+  }
+
+  #[test]
+  fn pretty() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    assert_eq!(
+      format!("{:#?}", g),
+      "[\n    [\n        1,\n        [\n            2,\n        ] => 3,\n    \
+       ] => 4,\n    5,\n]"
+    );
+    assert_eq!(format!("{:#?}", &g[2]), "[\n    2,\n] => 3");
+    assert_eq!(format!("{:#?}", GroveBuf::<i32>::new()), "[]");
+  }
+
+  #[test]
+  fn pretty_values() {
+    let g = grove_buf![[(1, 2)] => (3, 4)];
+    assert_eq!(
+      format!("{:#?}", g),
+      "[\n    [\n        (\n            1,\n            2,\n        ),\n    \
+       ] => (\n        3,\n        4,\n    ),\n]"
+    );
+  }
+
+  #[test]
+  fn deep_tree() {
+    let g = crate::internal::deep_tree();
+    let s = format!("{:?}", g);
+    assert!(s.starts_with("[[[["));
+    assert!(s.contains("[0] => 1] => 2] => 3"));
+    assert!(s.ends_with("] => 99999]"));
+  }
+}
@@ -0,0 +1,1163 @@
+use crate::grove::Grove;
+use crate::grove_buf::GroveBuf;
+use crate::internal::get_tree;
+use crate::node::to_width;
+use crate::node::Node;
+use crate::node::Width;
+use crate::tree::Tree;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+impl<T, W: Width> GroveBuf<T, W> {
+  /// Returns the index of the first node in the subtree rooted at `index`.
+  pub(crate) fn start(&self, index: usize) -> usize {
+    index + 1 - self.nodes[index].width()
+  }
+
+  /// Adds `delta` to the width of each proper ancestor of the node at `index`.
+  ///
+  /// # Panics
+  ///
+  /// Panics without changing any width if a new width is not representable by
+  /// `W`.
+  fn resize_ancestors(&mut self, index: usize, delta: isize) {
+    let start = self.start(index);
+    let resized = |node: &Node<T, W>| -> W {
+      to_width(node.width().checked_add_signed(delta).unwrap())
+    };
+    // Every width is checked before any is written, so that an overflow
+    // leaves `self` unchanged.
+    for (j, node) in self.nodes.iter().enumerate().skip(index + 1) {
+      if j + 1 - node.width() <= start {
+        resized(node);
+      }
+    }
+    for (j, node) in self.nodes.iter_mut().enumerate().skip(index + 1) {
+      if j + 1 - node.width() <= start {
+        node.width = resized(node);
+      }
+    }
+  }
+
+  /// Removes every node `i` for which `removed[i]` is `true` in a single pass,
+  /// shrinking the width of each remaining node by the number of removed nodes
+  /// it contained.
+  fn remove_nodes(&mut self, removed: &[bool]) {
+    let mut removed_before = Vec::with_capacity(removed.len() + 1);
+    removed_before.push(0);
+    for &r in removed {
+      removed_before.push(removed_before.last().unwrap() + r as usize);
+    }
+    for (i, node) in self.nodes.iter_mut().enumerate() {
+      let start = i + 1 - node.width();
+      let count = removed_before[i] - removed_before[start];
+      node.width = to_width(node.width() - count);
+    }
+    let mut flags = removed.iter();
+    self.nodes.retain(|_| !flags.next().unwrap());
+    if !self.deleted.is_empty() {
+      let mut flags = removed.iter();
+      self.deleted.retain(|_| !flags.next().unwrap());
+    }
+  }
+
+  /// Replaces the deletion marks of the nodes in `range` with `marks`, which
+  /// holds one mark for each of `count` new nodes or is empty if none of them
+  /// is marked, and returns the replaced marks in the same form. Must be
+  /// called before the nodes themselves are replaced.
+  fn splice_deleted(
+    &mut self,
+    range: Range<usize>,
+    count: usize,
+    marks: Vec<bool>,
+  ) -> Vec<bool> {
+    if self.deleted.is_empty() && marks.is_empty() {
+      return vec![];
+    }
+    self.deleted.resize(self.nodes.len(), false);
+    let marks = if marks.is_empty() {
+      vec![false; count]
+    } else {
+      marks
+    };
+    let replaced: Vec<_> = self.deleted.splice(range, marks).collect();
+    if replaced.contains(&true) {
+      replaced
+    } else {
+      vec![]
+    }
+  }
+
+  /// Returns the marks for `count` new nodes placed among the children of
+  /// `parent`, or among the top-level trees if it is `None`. These are all
+  /// `true` if `parent` is marked deleted, since a deleted node's subtree is
+  /// deleted as a whole, and `marks`, in the form taken by `splice_deleted`,
+  /// otherwise.
+  fn inherit_deleted(
+    &self,
+    parent: Option<usize>,
+    count: usize,
+    marks: Vec<bool>,
+  ) -> Vec<bool> {
+    if parent.is_some_and(|parent| self.is_deleted(parent)) {
+      vec![true; count]
+    } else {
+      marks
+    }
+  }
+
+  /// Returns the index of the parent of the node at `index`, or `None` if it
+  /// is the root of a top-level tree.
+  fn parent(&self, index: usize) -> Option<usize> {
+    let start = self.start(index);
+    (index + 1..self.nodes.len()).find(|&j| self.start(j) <= start)
+  }
+
+  /// Returns `true` if and only if the nodes at `a` and `b`, where `a <= b`,
+  /// are the same node or roots of sibling subtrees.
+  fn are_siblings(&self, a: usize, b: usize) -> bool {
+    a == b || (self.start(b) > a && self.parent(a) == self.parent(b))
+  }
+
+  /// Returns an iterator over the first node and root of each tree in
+  /// `self.nodes[start..end]`, from right to left.
+  fn roots_rev(
+    &self,
+    start: usize,
+    end: usize,
+  ) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut position = end;
+    std::iter::from_fn(move || {
+      (position > start).then(|| {
+        let root = position - 1;
+        position -= self.nodes[root].width();
+        (position, root)
+      })
+    })
+  }
+
+  /// Returns the index of the first node of the tree with the given ordinal
+  /// among the trees in `self.nodes[start..end]`, or `end` if there are
+  /// exactly `ordinal` such trees. If `skip` holds the root of one of these
+  /// trees, that tree is ignored when counting. Returns `None` if there are
+  /// fewer than `ordinal` trees.
+  fn tree_position(
+    &self,
+    start: usize,
+    end: usize,
+    ordinal: usize,
+    skip: Option<usize>,
+  ) -> Option<usize> {
+    let roots = || {
+      self
+        .roots_rev(start, end)
+        .filter(move |&(_, root)| skip != Some(root))
+    };
+    let count = roots().count();
+    match ordinal.cmp(&count) {
+      Ordering::Less => Some(roots().nth(count - 1 - ordinal).unwrap().0),
+      Ordering::Equal => Some(end),
+      Ordering::Greater => None,
+    }
+  }
+
+  /// Analogous to [`tree_position`][GroveBuf::tree_position] for the children
+  /// of `parent`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `parent` has fewer than `ordinal` children.
+  fn child_position(
+    &self,
+    parent: usize,
+    ordinal: usize,
+    skip: Option<usize>,
+  ) -> usize {
+    self
+      .tree_position(self.start(parent), parent, ordinal, skip)
+      .expect("child ordinal out of bounds")
+  }
+
+  /// Removes the last top-level tree and returns it, or `None` if `self` is
+  /// empty.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+  /// assert_eq!(g.pop_tree(), Some(grove_buf![4]));
+  /// assert_eq!(g.pop_tree(), Some(grove_buf![[1, 2] => 3]));
+  /// assert_eq!(g.pop_tree(), None);
+  /// ```
+  pub fn pop_tree(&mut self) -> Option<GroveBuf<T, W>> {
+    let root = self.nodes.len().checked_sub(1)?;
+    let start = self.start(root);
+    let deleted = self.splice_deleted(start..root + 1, 0, vec![]);
+    Some(GroveBuf {
+      nodes: self.nodes.split_off(start),
+      deleted,
+    })
+  }
+
+  /// Keeps the first `n` top-level trees and drops the rest. Has no effect if
+  /// there are at most `n` top-level trees.
+  pub fn truncate_trees(&mut self, n: usize) {
+    if let Some(position) = self.tree_position(0, self.nodes.len(), n, None) {
+      self.nodes.truncate(position);
+      self.deleted.truncate(position);
+    }
+  }
+
+  /// Splits `self` into two at the boundary before the top-level tree with
+  /// ordinal `k`. Returns a [`GroveBuf`] holding the trees from ordinal `k`
+  /// onward, leaving the first `k` top-level trees in `self`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if there are fewer than `k` top-level trees.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4, [5] => 6];
+  /// assert_eq!(g.split_off_trees(1), grove_buf![4, [5] => 6]);
+  /// assert_eq!(g, grove_buf![[1, 2] => 3]);
+  /// ```
+  pub fn split_off_trees(&mut self, k: usize) -> GroveBuf<T, W> {
+    let position = self
+      .tree_position(0, self.nodes.len(), k, None)
+      .expect("tree ordinal out of bounds");
+    let deleted = self.splice_deleted(position..self.nodes.len(), 0, vec![]);
+    GroveBuf {
+      nodes: self.nodes.split_off(position),
+      deleted,
+    }
+  }
+
+  /// Removes the subtree rooted at `index` and returns it. All later nodes are
+  /// shifted down to fill the gap, and the widths of the ancestors of `index`
+  /// are reduced accordingly. This takes time linear in the number of nodes
+  /// after the removed subtree.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// assert_eq!(g.remove_tree(3), grove_buf![[2, 3] => 4]);
+  /// assert_eq!(g, grove_buf![[1, 5] => 6, 7]);
+  /// ```
+  pub fn remove_tree(&mut self, index: usize) -> GroveBuf<T, W> {
+    let width = self.nodes[index].width();
+    self.resize_ancestors(index, -(width as isize));
+    let range = index + 1 - width..index + 1;
+    GroveBuf {
+      deleted: self.splice_deleted(range.clone(), 0, vec![]),
+      nodes: self.nodes.drain(range).collect(),
+    }
+  }
+
+  /// Removes the node at `index` and returns its value. Its children take its
+  /// place among the children of its parent (or among the top-level trees),
+  /// and the widths of its ancestors are reduced by one. This takes time linear
+  /// in the number of nodes after `index`.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// assert_eq!(g.dissolve(3), 4);
+  /// assert_eq!(g, grove_buf![[1, 2, 3, 5] => 6, 7]);
+  /// ```
+  pub fn dissolve(&mut self, index: usize) -> T {
+    self.resize_ancestors(index, -1);
+    self.splice_deleted(index..index + 1, 0, vec![]);
+    self.nodes.remove(index).value
+  }
+
+  /// Inserts a node with value `value` as the parent of the sibling subtrees
+  /// rooted at `first` through `last`, inclusive, which must either be
+  /// children of the same node or be top-level trees. The new node is placed
+  /// at index `last + 1` and the widths of its ancestors are increased by one.
+  /// This takes time linear in the number of nodes after `last`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `first` and `last` are not roots of sibling subtrees with
+  /// `first` preceding `last`, or if the width of any enlarged subtree is not
+  /// representable by `W`. In either case `self` is left unchanged.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// g.wrap_trees(0, 3, 8);
+  /// assert_eq!(g, grove_buf![[[1, [2, 3] => 4] => 8, 5] => 6, 7]);
+  /// ```
+  pub fn wrap_trees(&mut self, first: usize, last: usize, value: T) {
+    assert!(
+      first <= last && self.are_siblings(first, last),
+      "trees are not siblings"
+    );
+    let node = Node::new(value, last + 2 - self.start(first));
+    // The parent can only be marked if the wrapped trees are.
+    let parent = self.is_deleted(last).then(|| self.parent(last)).flatten();
+    let marks = self.inherit_deleted(parent, 1, vec![]);
+    self.resize_ancestors(last, 1);
+    self.splice_deleted(last + 1..last + 1, 1, marks);
+    self.nodes.insert(last + 1, node);
+  }
+
+  /// Removes every subtree for which `pred` returns `false`. Trees are visited
+  /// in [`ReversePostorder`][crate::ReversePostorder], and `pred` is not
+  /// invoked on the subtrees of a removed tree. All widths are recomputed in a
+  /// single compaction pass, so this takes time linear in the number of nodes.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// g.retain_trees(|t| *t.root() % 2 == 1 || t.len() > 1);
+  /// assert_eq!(g, grove_buf![[1, [3] => 4, 5] => 6, 7]);
+  /// ```
+  pub fn retain_trees(&mut self, mut pred: impl FnMut(&Tree<T, W>) -> bool) {
+    let mut removed = vec![false; self.nodes.len()];
+    let mut end = self.nodes.len();
+    while end > 0 {
+      let root = end - 1;
+      let start = self.start(root);
+      if pred(get_tree(&self.nodes[root])) {
+        end = root;
+      } else {
+        removed[start..=root].fill(true);
+        end = start;
+      }
+    }
+    self.remove_nodes(&removed);
+  }
+
+  /// Marks every node in the subtree rooted at `index` as deleted, without
+  /// moving any nodes. This takes time linear in the size of the subtree, plus
+  /// linear in the number of nodes the first time any node is marked.
+  ///
+  /// Deleted nodes remain in the [`GroveBuf`] until
+  /// [`compact`][GroveBuf::compact] is called: they keep their indices, count
+  /// towards [`len`][GroveBuf::len], and are seen by every operation on the
+  /// grove as a whole, such as comparisons, [`Debug`][std::fmt::Debug] and
+  /// [`Display`][std::fmt::Display] output, [`shape`][GroveBuf::shape] and
+  /// the scans. Only the traversals of the [`GroveBuf`] itself
+  /// ([`nodes`][GroveBuf::nodes], [`trees`][GroveBuf::trees],
+  /// [`visit`][GroveBuf::visit] and their mutable counterparts) skip them.
+  /// [`Grove`] and [`Tree`] views, including the trees yielded by
+  /// [`trees`][GroveBuf::trees] and the ones passed to a visitor, hold no
+  /// marks, so their lengths, children and scans include any deleted
+  /// descendants.
+  ///
+  /// The subtree of a deleted node is always deleted as a whole: edits that
+  /// place nodes under a deleted node, such as
+  /// [`insert_tree_buf`][GroveBuf::insert_tree_buf],
+  /// [`wrap_trees`][GroveBuf::wrap_trees] or
+  /// [`move_tree`][GroveBuf::move_tree], mark those nodes deleted too, while a
+  /// root pushed above deleted trees with [`push_root`][GroveBuf::push_root]
+  /// is not marked. Operations that produce a [`GroveBuf`] of the same shape
+  /// carry the marks over, and conversions that consume `self` into another
+  /// type, such as [`into_parts`][GroveBuf::into_parts], compact it first.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// g.mark_deleted(3);
+  /// g.mark_deleted(4);
+  /// let v: Vec<_> = g.nodes(Preorder).collect();
+  /// assert_eq!(v, vec![&1, &6, &7]);
+  /// assert_eq!(g.len(), 7);
+  /// g.compact();
+  /// assert_eq!(g, grove_buf![[1] => 6, 7]);
+  /// ```
+  pub fn mark_deleted(&mut self, index: usize) {
+    let start = self.start(index);
+    self.deleted.resize(self.nodes.len(), false);
+    self.deleted[start..=index].fill(true);
+  }
+
+  /// Returns `true` if and only if the node at `index` has been marked deleted.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn is_deleted(&self, index: usize) -> bool {
+    assert!(index < self.nodes.len(), "index out of bounds");
+    self.deleted.get(index) == Some(&true)
+  }
+
+  /// Removes every node marked deleted by
+  /// [`mark_deleted`][GroveBuf::mark_deleted], recomputing all widths in a
+  /// single pass. This takes time linear in the number of nodes, and has no
+  /// cost if no node is marked.
+  pub fn compact(&mut self) {
+    let removed = std::mem::take(&mut self.deleted);
+    if !removed.is_empty() {
+      self.remove_nodes(&removed);
+    }
+  }
+
+  /// Replaces the subtree rooted at `index` with the trees of `replacement`,
+  /// returning the removed subtree. The replacement need not have the same
+  /// number of nodes; later nodes are shifted as needed and the widths of the
+  /// ancestors of `index` are corrected accordingly. This takes time linear in
+  /// the number of nodes after the replaced subtree.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of any enlarged subtree is not representable by `W`,
+  /// leaving `self` unchanged.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// assert_eq!(g.replace_tree(3, grove_buf![8]), grove_buf![[2, 3] => 4]);
+  /// assert_eq!(g, grove_buf![[1, 8, 5] => 6, 7]);
+  /// ```
+  pub fn replace_tree(
+    &mut self,
+    index: usize,
+    replacement: GroveBuf<T, W>,
+  ) -> GroveBuf<T, W> {
+    let width = self.nodes[index].width();
+    let count = replacement.nodes.len();
+    // The parent can only be marked if the replaced tree is.
+    let parent = self.is_deleted(index).then(|| self.parent(index)).flatten();
+    let marks = self.inherit_deleted(parent, count, replacement.deleted);
+    self.resize_ancestors(index, count as isize - width as isize);
+    let range = index + 1 - width..index + 1;
+    GroveBuf {
+      deleted: self.splice_deleted(range.clone(), count, marks),
+      nodes: self.nodes.splice(range, replacement.nodes).collect(),
+    }
+  }
+
+  /// Inserts a copy of each tree in `subtree` as children of the node at
+  /// `parent`, so that the first of them becomes the child with ordinal
+  /// `ordinal`. The widths of `parent` and its ancestors are increased
+  /// accordingly. This takes time linear in the number of nodes after the
+  /// insertion point.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `parent` has fewer than `ordinal` children, or if the width of
+  /// any enlarged subtree is not representable by `W`. In either case `self`
+  /// is left unchanged.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+  /// g.insert_tree(2, 1, grove_buf![[5] => 6, 7].as_ref());
+  /// assert_eq!(g, grove_buf![[1, [5] => 6, 7, 2] => 3, 4]);
+  /// ```
+  pub fn insert_tree(
+    &mut self,
+    parent: usize,
+    ordinal: usize,
+    subtree: &Grove<T, W>,
+  ) where
+    T: Clone,
+  {
+    self.insert_tree_buf(parent, ordinal, subtree.map_ref(T::clone))
+  }
+
+  /// Analogous to [`insert_tree`][GroveBuf::insert_tree], but moves the nodes
+  /// of `subtree` rather than copying them.
+  pub fn insert_tree_buf(
+    &mut self,
+    parent: usize,
+    ordinal: usize,
+    subtree: GroveBuf<T, W>,
+  ) {
+    let position = self.child_position(parent, ordinal, None);
+    let count = subtree.nodes.len();
+    let width = to_width(self.nodes[parent].width() + count);
+    let marks = self.inherit_deleted(Some(parent), count, subtree.deleted);
+    self.resize_ancestors(parent, count as isize);
+    self.nodes[parent].width = width;
+    self.splice_deleted(position..position, count, marks);
+    self.nodes.splice(position..position, subtree.nodes);
+  }
+
+  /// Exchanges the positions of the sibling subtrees rooted at `a` and `b`,
+  /// which may have different sizes. Both must either be children of the same
+  /// node or be top-level trees. No ancestor widths change, so this is done
+  /// with in-place rotations of the nodes between the two subtrees. Swapping a
+  /// tree with itself has no effect.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `a` and `b` are not roots of sibling subtrees.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, 7];
+  /// g.swap_trees(0, 4);
+  /// assert_eq!(g, grove_buf![[5, [2, 3] => 4, 1] => 6, 7]);
+  /// ```
+  pub fn swap_trees(&mut self, a: usize, b: usize) {
+    let (a, b) = (a.min(b), a.max(b));
+    assert!(self.are_siblings(a, b), "trees are not siblings");
+    if a == b {
+      return;
+    }
+    let a_start = self.start(a);
+    let a_width = self.nodes[a].width();
+    let b_start = self.start(b);
+    // Moves the `a_width` items at the front of `region` to its back, then
+    // the `gap` items between the two subtrees behind the other subtree.
+    fn swap<X>(region: &mut [X], a_width: usize, gap: usize) {
+      region.rotate_left(a_width);
+      let end = region.len() - a_width;
+      region[..end].rotate_left(gap);
+    }
+    let gap = b_start - a - 1;
+    swap(&mut self.nodes[a_start..=b], a_width, gap);
+    if !self.deleted.is_empty() {
+      swap(&mut self.deleted[a_start..=b], a_width, gap);
+    }
+  }
+
+  /// Moves the subtree rooted at `index` so that it becomes the child of
+  /// `new_parent` with the given ordinal, counted among the other children of
+  /// `new_parent`, or the top-level tree with that ordinal if `new_parent` is
+  /// `None`. The nodes between the old and new positions are rotated in place
+  /// and the widths of the affected ancestors are adjusted, so no nodes are
+  /// copied out of the buffer.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `new_parent` is in the subtree rooted at `index`, if there are
+  /// fewer than `ordinal` other children of `new_parent` (or other top-level
+  /// trees), or if the width of any enlarged subtree is not representable by
+  /// `W`. In each case `self` is left unchanged.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4, 5] => 6, [7] => 8];
+  /// g.move_tree(3, Some(7), 0);
+  /// assert_eq!(g, grove_buf![[1, 5] => 6, [[2, 3] => 4, 7] => 8]);
+  /// g.move_tree(5, None, 1);
+  /// assert_eq!(g, grove_buf![[1, 5] => 6, [2, 3] => 4, [7] => 8]);
+  /// ```
+  pub fn move_tree(
+    &mut self,
+    index: usize,
+    new_parent: Option<usize>,
+    ordinal: usize,
+  ) {
+    let start = self.start(index);
+    let width = self.nodes[index].width();
+    let (first, target, entering) = match new_parent {
+      Some(parent) => {
+        assert!(
+          parent < start || parent > index,
+          "cannot move a tree into itself"
+        );
+        let target = self.child_position(parent, ordinal, Some(index));
+        (start.min(parent), target, Some((parent, self.start(parent))))
+      }
+      None => {
+        let target = self
+          .tree_position(0, self.nodes.len(), ordinal, Some(index))
+          .expect("tree ordinal out of bounds");
+        (start, target, None)
+      }
+    };
+    // Every new width is computed before any is written, so that an overflow
+    // leaves `self` unchanged.
+    let widths: Vec<(usize, W)> = (first..self.nodes.len())
+      .filter_map(|j| {
+        let j_start = self.start(j);
+        let leaving = j > index && j_start <= start;
+        let entering = entering.is_some_and(|(parent, parent_start)| {
+          j >= parent && j_start <= parent_start
+        });
+        let j_width = self.nodes[j].width();
+        match (leaving, entering) {
+          (false, true) => Some((j, to_width(j_width + width))),
+          (true, false) => Some((j, to_width(j_width - width))),
+          _ => None,
+        }
+      })
+      .collect();
+    let inherit = self.inherit_deleted(new_parent, width, vec![]);
+    for (j, width) in widths {
+      self.nodes[j].width = width;
+    }
+    let moved = if target <= start {
+      self.nodes[target..=index].rotate_right(width);
+      if !self.deleted.is_empty() {
+        self.deleted[target..=index].rotate_right(width);
+      }
+      target..target + width
+    } else {
+      self.nodes[start..target].rotate_left(width);
+      if !self.deleted.is_empty() {
+        self.deleted[start..target].rotate_left(width);
+      }
+      target - width..target
+    };
+    if !inherit.is_empty() {
+      self.deleted[moved].fill(true);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::grove_buf;
+  use crate::grove_buf::GroveBuf;
+  use crate::traversal::Preorder;
+  use crate::traversal::ReversePostorder;
+  use crate::tree::Tree;
+  use crate::visitor::Visitor;
+  use std::panic::catch_unwind;
+  use std::panic::AssertUnwindSafe;
+
+  fn example() -> GroveBuf<i32> {
+    grove_buf![[[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9, [10] => 11]
+  }
+
+  // Returns a tree of 255 nodes, the most a `u8` width allows. Its root at
+  // index 254 has 252 leaves and the subtree `[252] => 253` as children.
+  fn full() -> GroveBuf<i32, u8> {
+    let mut g = GroveBuf::default();
+    for i in 0..253 {
+      g.push(i);
+    }
+    g.push_root(253, 1);
+    g.push_root(254, 253);
+    g
+  }
+
+  // Asserts that `edit` panics and leaves `g` unchanged.
+  fn assert_unchanged_by_panic(
+    mut g: GroveBuf<i32, u8>,
+    edit: impl FnOnce(&mut GroveBuf<i32, u8>),
+  ) {
+    let before = g.clone();
+    let result = catch_unwind(AssertUnwindSafe(|| edit(&mut g)));
+    assert!(result.is_err());
+    assert_eq!(g, before);
+    assert_eq!(g.deleted, before.deleted);
+  }
+
+  #[test]
+  fn pop() {
+    let mut g = example();
+    assert_eq!(g.pop_tree(), Some(grove_buf![[10] => 11]));
+    assert_eq!(g.len(), 9);
+    assert_eq!(g.pop_tree().map(|t| t.len()), Some(9));
+    assert_eq!(g.pop_tree(), None);
+  }
+
+  #[test]
+  fn truncate() {
+    let mut g = grove_buf![[1, 2] => 3, 4, [5] => 6];
+    g.truncate_trees(3);
+    assert_eq!(g, grove_buf![[1, 2] => 3, 4, [5] => 6]);
+    g.truncate_trees(2);
+    assert_eq!(g, grove_buf![[1, 2] => 3, 4]);
+    g.truncate_trees(0);
+    assert!(g.is_empty());
+  }
+
+  #[test]
+  fn split_off() {
+    let mut g = example();
+    assert!(g.split_off_trees(2).is_empty());
+    assert_eq!(g.split_off_trees(1), grove_buf![[10] => 11]);
+    assert_eq!(g.split_off_trees(0).len(), 9);
+    assert!(g.is_empty());
+  }
+
+  #[test]
+  #[should_panic(expected = "tree ordinal out of bounds")]
+  fn split_off_out_of_bounds() {
+    example().split_off_trees(3);
+  }
+
+  #[test]
+  fn remove_leaf() {
+    let mut g = example();
+    assert_eq!(g.remove_tree(3), grove_buf![4]);
+    assert_eq!(
+      g,
+      grove_buf![[[1, 2] => 3, [5, [6] => 7] => 8] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  fn remove_nested() {
+    let mut g = example();
+    assert_eq!(g.remove_tree(6), grove_buf![[6] => 7]);
+    assert_eq!(g, grove_buf![[[1, 2] => 3, 4, [5] => 8] => 9, [10] => 11]);
+    assert_eq!(g.remove_tree(2), grove_buf![[1, 2] => 3]);
+    assert_eq!(g, grove_buf![[4, [5] => 8] => 9, [10] => 11]);
+  }
+
+  #[test]
+  fn remove_top_level() {
+    let mut g = example();
+    assert_eq!(
+      g.remove_tree(8),
+      grove_buf![[[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9]
+    );
+    assert_eq!(g, grove_buf![[10] => 11]);
+    g.remove_tree(1);
+    assert!(g.is_empty());
+  }
+
+  #[test]
+  fn dissolve() {
+    let mut g = example();
+    assert_eq!(g.dissolve(7), 8);
+    assert_eq!(g, grove_buf![[[1, 2] => 3, 4, 5, [6] => 7] => 9, [10] => 11]);
+    assert_eq!(g.dissolve(7), 9);
+    assert_eq!(g, grove_buf![[1, 2] => 3, 4, 5, [6] => 7, [10] => 11]);
+    assert_eq!(g.dissolve(3), 4);
+    assert_eq!(g, grove_buf![[1, 2] => 3, 5, [6] => 7, [10] => 11]);
+  }
+
+  #[test]
+  fn wrap_children() {
+    let mut g = example();
+    g.wrap_trees(3, 7, 20);
+    assert_eq!(
+      g,
+      grove_buf![[[1, 2] => 3, [4, [5, [6] => 7] => 8] => 20] => 9, [10] => 11]
+    );
+    g.wrap_trees(5, 5, 21);
+    assert_eq!(
+      g,
+      grove_buf![
+        [[1, 2] => 3, [4, [5, [[6] => 21] => 7] => 8] => 20] => 9,
+        [10] => 11
+      ]
+    );
+  }
+
+  #[test]
+  fn wrap_overflow() {
+    assert_unchanged_by_panic(full(), |g| g.wrap_trees(252, 252, 0));
+    let mut g = full();
+    g.mark_deleted(0);
+    assert_unchanged_by_panic(g, |g| g.wrap_trees(254, 254, 0));
+  }
+
+  #[test]
+  fn wrap_top_level() {
+    let mut g = example();
+    g.wrap_trees(8, 10, 20);
+    assert_eq!(
+      g,
+      grove_buf![[[[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9, [10] => 11] => 20]
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "trees are not siblings")]
+  fn wrap_non_siblings() {
+    example().wrap_trees(3, 9, 20);
+  }
+
+  #[test]
+  fn retain() {
+    let mut g = example();
+    let mut visited = vec![];
+    g.retain_trees(|t| {
+      visited.push(*t.root());
+      *t.root() != 3 && *t.root() != 7 && *t.root() != 10
+    });
+    assert_eq!(visited, vec![11, 10, 9, 8, 7, 5, 4, 3]);
+    assert_eq!(g, grove_buf![[4, [5] => 8] => 9, 11]);
+  }
+
+  #[test]
+  fn retain_none() {
+    let mut g = example();
+    g.retain_trees(|t| t.len() == 1);
+    assert!(g.is_empty());
+    let mut g = example();
+    g.retain_trees(|_| true);
+    assert_eq!(g, example());
+  }
+
+  #[test]
+  fn tombstones() {
+    let mut g = example();
+    g.mark_deleted(6);
+    g.mark_deleted(0);
+    g.mark_deleted(9);
+    assert!(g.is_deleted(5));
+    assert!(!g.is_deleted(7));
+    assert_eq!(g.len(), 11);
+
+    let v: Vec<_> = g.nodes(ReversePostorder).collect();
+    assert_eq!(v, vec![&11, &9, &8, &5, &4, &3, &2]);
+    let v: Vec<_> = g.trees(Preorder).map(Tree::len).collect();
+    assert_eq!(v, vec![1, 3, 1, 1, 4, 9, 2]);
+    for n in g.nodes_mut(Preorder) {
+      *n += 100;
+    }
+    let v: Vec<_> = g.as_ref().nodes(Preorder).copied().collect();
+    assert_eq!(v, vec![1, 102, 103, 104, 105, 6, 7, 108, 109, 10, 111]);
+
+    g.remove_tree(3);
+    g.compact();
+    assert_eq!(g, grove_buf![[[102] => 103, [105] => 108] => 109, 111]);
+    assert!(!(0..g.len()).any(|i| g.is_deleted(i)));
+  }
+
+  // Returns the values of the nodes of `g` that are not marked deleted, in
+  // `Preorder`, after checking that `nodes` and `visit` agree on them.
+  fn live(g: &GroveBuf<i32>) -> Vec<i32> {
+    struct Values(Vec<i32>);
+    impl Visitor<i32> for Values {
+      fn leave(&mut self, tree: &Tree<i32>) {
+        self.0.push(*tree.root());
+      }
+    }
+    let mut visited = Values(vec![]);
+    g.visit(&mut visited);
+    let nodes: Vec<_> = g.nodes(Preorder).copied().collect();
+    assert_eq!(nodes, visited.0);
+    nodes
+  }
+
+  #[test]
+  fn tombstones_cover_placed_nodes() {
+    let mut g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+    g.mark_deleted(1);
+    g.insert_tree_buf(1, 0, grove_buf![9]);
+    assert!(g.is_deleted(1) && g.is_deleted(2));
+    assert_eq!(live(&g), vec![1, 3, 4]);
+    g.compact();
+    assert_eq!(g, grove_buf![[1] => 3, 4]);
+
+    let mut g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+    g.mark_deleted(2);
+    g.wrap_trees(0, 1, 5);
+    assert!(g.is_deleted(2));
+    g.replace_tree(0, grove_buf![6, 7]);
+    assert!((0..5).all(|i| g.is_deleted(i)));
+    g.move_tree(5, Some(4), 0);
+    assert!(g.is_deleted(0));
+    assert_eq!(live(&g), vec![]);
+    g.compact();
+    assert!(g.is_empty());
+  }
+
+  #[test]
+  fn tombstones_under_live_nodes() {
+    let mut g: GroveBuf<i32> = grove_buf![1, 2];
+    g.mark_deleted(0);
+    g.push_root(3, 2);
+    assert!(!g.is_deleted(2));
+    assert_eq!(live(&g), vec![2, 3]);
+    let root = g.trees(Preorder).last().unwrap();
+    assert_eq!(root.len(), 3);
+    g.compact();
+    assert_eq!(g, grove_buf![[2] => 3]);
+  }
+
+  #[test]
+  fn tombstones_in_scans() {
+    let mut g: GroveBuf<i32> = grove_buf![[1, 2] => 3];
+    g.mark_deleted(0);
+    let depths =
+      g.scan_down(|_, parent: Option<&i32>, _| parent.map_or(0, |d| d + 1));
+    assert_eq!(depths, grove_buf![[1, 1] => 0]);
+    assert!(depths.is_deleted(0));
+    let sizes = g.scan_up(|_, children| 1 + children.iter().sum::<i32>());
+    assert_eq!(sizes, grove_buf![[1, 1] => 3]);
+    assert!(sizes.is_deleted(0) && !sizes.is_deleted(1));
+  }
+
+  #[test]
+  fn tombstones_remain_until_compacted() {
+    let mut g = example();
+    g.mark_deleted(2);
+    assert_eq!(g, example());
+    assert_eq!(format!("{:?}", g), format!("{:?}", example()));
+    assert!(g.same_shape(&example()));
+    assert_eq!(g.shape(), example().shape());
+    assert!(g.clone().map(|n| n * 2).is_deleted(1));
+
+    let compacted = grove_buf![[4, [5, [6] => 7] => 8] => 9, [10] => 11];
+    let (shape, values) = g.clone().into_parts();
+    assert_eq!(shape.with_values(values), Ok(compacted.clone()));
+    g.compact();
+    assert_eq!(g, compacted);
+  }
+
+  #[test]
+  fn tombstones_follow_edits() {
+    let mut g = example();
+    g.mark_deleted(3);
+    g.swap_trees(2, 7);
+    assert!(g.is_deleted(4) && !g.is_deleted(3));
+    g.move_tree(4, Some(10), 0);
+    assert!(g.is_deleted(8));
+    let mut h: GroveBuf<i32> = grove_buf![20, 21];
+    h.mark_deleted(0);
+    g.append(&mut h);
+    g.push_root(22, 2);
+    assert!(g.is_deleted(11) && !g.is_deleted(13));
+
+    let mut t = g.pop_tree().unwrap();
+    assert!(t.is_deleted(0));
+    t.compact();
+    assert_eq!(t, grove_buf![[21] => 22]);
+    g.compact();
+    assert_eq!(
+      g,
+      grove_buf![[[5, [6] => 7] => 8, [1, 2] => 3] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  fn replace_grow() {
+    let mut g = example();
+    assert_eq!(g.replace_tree(3, grove_buf![[20, 21] => 22]), grove_buf![4]);
+    assert_eq!(
+      g,
+      grove_buf![
+        [[1, 2] => 3, [20, 21] => 22, [5, [6] => 7] => 8] => 9,
+        [10] => 11
+      ]
+    );
+  }
+
+  #[test]
+  fn replace_shrink() {
+    let mut g = example();
+    assert_eq!(
+      g.replace_tree(7, grove_buf![20, 21]),
+      grove_buf![[5, [6] => 7] => 8]
+    );
+    assert_eq!(g, grove_buf![[[1, 2] => 3, 4, 20, 21] => 9, [10] => 11]);
+    g.replace_tree(5, GroveBuf::new());
+    assert_eq!(g, grove_buf![[[1, 2] => 3, 4, 20] => 9, [10] => 11]);
+  }
+
+  #[test]
+  fn replace_overflow() {
+    assert_unchanged_by_panic(full(), |g| {
+      g.replace_tree(252, grove_buf![@width u8; [0] => 1]);
+    });
+    let mut replacement = grove_buf![@width u8; 0, 1];
+    replacement.mark_deleted(0);
+    assert_unchanged_by_panic(full(), |g| {
+      g.replace_tree(252, replacement);
+    });
+  }
+
+  #[test]
+  fn swap_children() {
+    let mut g = example();
+    g.swap_trees(7, 2);
+    assert_eq!(
+      g,
+      grove_buf![[[5, [6] => 7] => 8, 4, [1, 2] => 3] => 9, [10] => 11]
+    );
+    g.swap_trees(4, 7);
+    assert_eq!(
+      g,
+      grove_buf![[[5, [6] => 7] => 8, [1, 2] => 3, 4] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  fn swap_top_level() {
+    let mut g = example();
+    g.swap_trees(8, 10);
+    assert_eq!(
+      g,
+      grove_buf![[10] => 11, [[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9]
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "trees are not siblings")]
+  fn swap_non_siblings() {
+    example().swap_trees(2, 6);
+  }
+
+  #[test]
+  #[should_panic(expected = "trees are not siblings")]
+  fn swap_nested() {
+    example().swap_trees(2, 8);
+  }
+
+  #[test]
+  fn swap_with_itself() {
+    let mut g = example();
+    g.swap_trees(2, 2);
+    g.swap_trees(0, 0);
+    g.swap_trees(10, 10);
+    assert_eq!(g, example());
+  }
+
+  #[test]
+  fn move_right() {
+    let mut g = example();
+    g.move_tree(2, Some(10), 1);
+    assert_eq!(
+      g,
+      grove_buf![[4, [5, [6] => 7] => 8] => 9, [10, [1, 2] => 3] => 11]
+    );
+  }
+
+  #[test]
+  fn move_left() {
+    let mut g = example();
+    g.move_tree(6, Some(2), 0);
+    assert_eq!(
+      g,
+      grove_buf![[[[6] => 7, 1, 2] => 3, 4, [5] => 8] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  fn move_within_parent() {
+    let mut g = example();
+    g.move_tree(2, Some(8), 2);
+    assert_eq!(
+      g,
+      grove_buf![[4, [5, [6] => 7] => 8, [1, 2] => 3] => 9, [10] => 11]
+    );
+    g.move_tree(7, Some(8), 0);
+    assert_eq!(
+      g,
+      grove_buf![[[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  fn move_to_descendant_of_sibling() {
+    let mut g = example();
+    g.move_tree(3, Some(6), 1);
+    assert_eq!(
+      g,
+      grove_buf![[[1, 2] => 3, [5, [6, 4] => 7] => 8] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "cannot move a tree into itself")]
+  fn move_into_itself() {
+    example().move_tree(7, Some(5), 0);
+  }
+
+  #[test]
+  fn move_without_shifting() {
+    let mut g = example();
+    g.move_tree(0, Some(8), 0);
+    assert_eq!(
+      g,
+      grove_buf![[1, [2] => 3, 4, [5, [6] => 7] => 8] => 9, [10] => 11]
+    );
+  }
+
+  #[test]
+  fn move_to_top_level() {
+    let mut g = example();
+    g.move_tree(7, None, 0);
+    assert_eq!(
+      g,
+      grove_buf![[5, [6] => 7] => 8, [[1, 2] => 3, 4] => 9, [10] => 11]
+    );
+    g.move_tree(8, None, 2);
+    assert_eq!(
+      g,
+      grove_buf![[5, [6] => 7] => 8, [10] => 11, [[1, 2] => 3, 4] => 9]
+    );
+    g.move_tree(8, None, 1);
+    assert_eq!(
+      g,
+      grove_buf![[5, [6] => 7] => 8, [1, 2] => 3, [10] => 11, [4] => 9]
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "tree ordinal out of bounds")]
+  fn move_to_top_level_out_of_bounds() {
+    example().move_tree(2, None, 3);
+  }
+
+  #[test]
+  fn move_overflow() {
+    let mut g = full();
+    g.push(255);
+    assert_unchanged_by_panic(g, |g| g.move_tree(255, Some(252), 0));
+  }
+
+  #[test]
+  fn insert_first_and_last() {
+    let mut g = example();
+    g.insert_tree(7, 0, grove_buf![20, 21].as_ref());
+    assert_eq!(
+      g,
+      grove_buf![[[1, 2] => 3, 4, [20, 21, 5, [6] => 7] => 8] => 9, [10] => 11]
+    );
+    g.insert_tree_buf(9, 4, grove_buf![[22] => 23]);
+    assert_eq!(
+      g,
+      grove_buf![
+        [[1, 2] => 3, 4, [20, 21, 5, [6] => 7, [22] => 23] => 8] => 9,
+        [10] => 11
+      ]
+    );
+  }
+
+  #[test]
+  fn insert_into_leaf() {
+    let mut g = example();
+    g.insert_tree_buf(3, 0, grove_buf![[20] => 21]);
+    assert_eq!(
+      g,
+      grove_buf![
+        [[1, 2] => 3, [[20] => 21] => 4, [5, [6] => 7] => 8] => 9,
+        [10] => 11
+      ]
+    );
+  }
+
+  #[test]
+  fn insert_empty() {
+    let mut g = example();
+    g.insert_tree_buf(8, 1, GroveBuf::new());
+    assert_eq!(g, example());
+  }
+
+  #[test]
+  #[should_panic(expected = "child ordinal out of bounds")]
+  fn insert_out_of_bounds() {
+    example().insert_tree_buf(8, 4, grove_buf![1]);
+  }
+
+  #[test]
+  fn insert_overflow() {
+    assert_unchanged_by_panic(full(), |g| {
+      g.insert_tree_buf(252, 0, grove_buf![@width u8; 0])
+    });
+    assert_unchanged_by_panic(full(), |g| {
+      g.insert_tree_buf(254, 0, grove_buf![@width u8; 0])
+    });
+    let mut g = full();
+    g.mark_deleted(0);
+    assert_unchanged_by_panic(g, |g| {
+      g.insert_tree_buf(253, 1, grove_buf![@width u8; 0])
+    });
+  }
+}
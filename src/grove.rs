@@ -1,17 +1,32 @@
 use crate::internal::get_tree;
 use crate::internal::get_tree_mut;
+use crate::grove_buf::GroveBuf;
+use crate::internal::arc_as_grove_unchecked;
+use crate::internal::box_as_grove_unchecked;
+use crate::internal::rc_as_grove_unchecked;
 use crate::node::Node;
+use crate::node::Width;
+use crate::scan;
+use crate::scan::Operands;
+use crate::shape::Shape;
 use crate::traversal::TraversalOrder;
 use crate::tree::Tree;
+use crate::visitor;
+use crate::visitor::Visitor;
+use crate::visitor::VisitorMut;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// An unsized type referencing a collection of consecutive [`Tree`]s inside a
 /// [`GroveBuf`][crate::GroveBuf].
 #[repr(transparent)]
-pub struct Grove<T> {
-  pub(crate) nodes: [Node<T>],
+pub struct Grove<T, W = usize> {
+  pub(crate) nodes: [Node<T, W>],
 }
 
-impl<T> Grove<T> {
+impl<T, W: Width> Grove<T, W> {
   /// Returns `true` if and only if the [`Grove`] contains no trees.
   pub fn is_empty(&self) -> bool {
     self.nodes.is_empty()
@@ -57,7 +72,7 @@ impl<T> Grove<T> {
   pub fn trees<Order: TraversalOrder>(
     &self,
     order: Order,
-  ) -> impl std::iter::Iterator<Item = &Tree<T>> {
+  ) -> impl std::iter::Iterator<Item = &Tree<T, W>> {
     order.tree_iter(&self.nodes)
   }
 
@@ -66,13 +81,178 @@ impl<T> Grove<T> {
   pub fn trees_mut<Order: TraversalOrder>(
     &mut self,
     order: Order,
-  ) -> impl std::iter::Iterator<Item = &mut Tree<T>> {
+  ) -> impl std::iter::Iterator<Item = &mut Tree<T, W>> {
     order.tree_iter_mut(&mut self.nodes)
   }
+
+  /// Traverses each tree in the [`Grove`], invoking [`Visitor::enter`] on each
+  /// subtree before its children are visited and [`Visitor::leave`] after. The
+  /// traversal is iterative, so arbitrarily deep trees do not exhaust the
+  /// stack.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// struct Depths(usize, Vec<(i32, usize)>);
+  /// impl Visitor<i32> for Depths {
+  ///   fn enter(&mut self, tree: &Tree<i32>) {
+  ///     self.1.push((*tree.root(), self.0));
+  ///     self.0 += 1;
+  ///   }
+  ///   fn leave(&mut self, _: &Tree<i32>) {
+  ///     self.0 -= 1;
+  ///   }
+  /// }
+  ///
+  /// let g: GroveBuf<i32> = grove_buf![[1, [2] => 3] => 4, 5];
+  /// let mut d = Depths(0, vec![]);
+  /// g.as_ref().visit(&mut d);
+  /// assert_eq!(d.1, vec![(4, 0), (1, 1), (3, 1), (2, 2), (5, 0)]);
+  /// ```
+  pub fn visit<V: Visitor<T, W> + ?Sized>(&self, visitor: &mut V) {
+    visitor::visit(&self.nodes, |_| true, visitor)
+  }
+
+  /// Analogous to [`Grove::visit`], invoking the hooks of a [`VisitorMut`]
+  /// with mutable references.
+  pub fn visit_mut<V: VisitorMut<T, W> + ?Sized>(&mut self, visitor: &mut V) {
+    visitor::visit_mut(&mut self.nodes, |_| true, visitor)
+  }
+
+  /// Computes a value for every node from the node's own value and the values
+  /// already computed for its children (in left-to-right order), returning a
+  /// [`GroveBuf`] with the same shape as `self` holding the computed values.
+  /// Because children are stored before their parents, this is a single
+  /// forward pass over the nodes, keeping the values computed for nodes whose
+  /// parents have not been reached yet on a stack.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<i32> = grove_buf![[1, [2, 3] => 4] => 5, 6];
+  /// let sums = g.as_ref().scan_up(|n, children| {
+  ///   n + children.iter().sum::<i32>()
+  /// });
+  /// assert_eq!(sums, grove_buf![[1, [2, 3] => 9] => 15, 6]);
+  /// ```
+  pub fn scan_up<U>(&self, mut f: impl FnMut(&T, &[U]) -> U) -> GroveBuf<U, W> {
+    let values = scan::scan_up(
+      self.nodes.len(),
+      |i| self.nodes[i].width(),
+      |i, children| f(&self.nodes[i].value, children),
+    );
+    self.with_widths(values)
+  }
+
+  /// Computes a value for every node from the node's own value, the value
+  /// already computed for its parent (`None` for the root of each top-level
+  /// tree), and its ordinal among its siblings counting from the left. Returns
+  /// a [`GroveBuf`] with the same shape as `self` holding the computed values.
+  /// Nodes are visited in [`ReversePostorder`][crate::ReversePostorder], so
+  /// every parent is computed before its children.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<char> = grove_buf![['a', ['b'] => 'c'] => 'd', 'e'];
+  /// let depths = g
+  ///   .as_ref()
+  ///   .scan_down(|_, parent: Option<&usize>, _| parent.map_or(0, |d| d + 1));
+  /// assert_eq!(depths, grove_buf![[1, [2] => 1] => 0, 0]);
+  /// ```
+  pub fn scan_down<U>(
+    &self,
+    mut f: impl FnMut(&T, Option<&U>, usize) -> U,
+  ) -> GroveBuf<U, W> {
+    let values = scan::scan_down(
+      self.nodes.len(),
+      |i| self.nodes[i].width(),
+      |i, parent, ordinal| f(&self.nodes[i].value, parent, ordinal),
+    );
+    self.with_widths(values)
+  }
+
+  /// Evaluates the [`Grove`] as postfix code. Nodes are visited in
+  /// [`Preorder`][crate::Preorder] with an operand stack; for each node, the
+  /// results of its children are popped off the stack and passed (as
+  /// [`Operands`], in left-to-right order) to `f` along with the node's value,
+  /// and the result is pushed back onto the stack. Returns the result for each
+  /// top-level tree, in order.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<char> = grove_buf![['2', ['3', '4'] => '*'] => '+', '5'];
+  /// let v = g.as_ref().evaluate(|c, mut operands| match c {
+  ///   '+' => operands.next().unwrap() + operands.next().unwrap(),
+  ///   '*' => operands.next().unwrap() * operands.next().unwrap(),
+  ///   c => c.to_digit(10).unwrap(),
+  /// });
+  /// assert_eq!(v, vec![14, 5]);
+  /// ```
+  pub fn evaluate<U>(
+    &self,
+    mut f: impl FnMut(&T, Operands<'_, U>) -> U,
+  ) -> Vec<U> {
+    scan::evaluate(
+      self.nodes.len(),
+      |i| self.nodes[i].width(),
+      |i, operands| f(&self.nodes[i].value, operands),
+    )
+  }
+
+  /// Returns a [`GroveBuf`] with the same shape as `self` whose values are
+  /// obtained by applying `f` to each value in `self`.
+  pub fn map_ref<U>(&self, mut f: impl FnMut(&T) -> U) -> GroveBuf<U, W> {
+    GroveBuf {
+      nodes: self
+        .nodes
+        .iter()
+        .map(|node| Node {
+          value: f(&node.value),
+          width: node.width,
+        })
+        .collect(),
+      deleted: vec![],
+    }
+  }
+
+  /// Returns `true` if and only if `self` and `other` have the same shape;
+  /// that is, they have the same number of nodes and every node in `self` has
+  /// the same number of descendants as the corresponding node in `other`.
+  /// Values are not compared.
+  pub fn same_shape<U>(&self, other: &Grove<U, W>) -> bool {
+    self.nodes.len() == other.nodes.len()
+      && self.nodes.iter().zip(&other.nodes).all(|(l, r)| l.width == r.width)
+  }
+
+  /// Returns the [`Shape`] of `self`, discarding all values.
+  pub fn shape(&self) -> Shape<W> {
+    Shape {
+      widths: self.nodes.iter().map(|node| node.width).collect(),
+    }
+  }
+
+  // Returns a `GroveBuf` with the same shape as `self` holding `values`, which
+  // must have one value per node.
+  fn with_widths<U>(&self, values: Vec<U>) -> GroveBuf<U, W> {
+    GroveBuf {
+      nodes: self
+        .nodes
+        .iter()
+        .zip(values)
+        .map(|(node, value)| Node {
+          value,
+          width: node.width,
+        })
+        .collect(),
+      deleted: vec![],
+    }
+  }
 }
 
-impl<T> std::ops::Index<usize> for Grove<T> {
-  type Output = Tree<T>;
+impl<T, W: Width> std::ops::Index<usize> for Grove<T, W> {
+  type Output = Tree<T, W>;
 
   /// Returns a reference to the [`Tree`] whose root has the given index.
   fn index(&self, index: usize) -> &Self::Output {
@@ -80,13 +260,65 @@ impl<T> std::ops::Index<usize> for Grove<T> {
   }
 }
 
-impl<T> std::ops::IndexMut<usize> for Grove<T> {
+impl<T, W: Width> std::ops::IndexMut<usize> for Grove<T, W> {
   /// Returns a reference to the [`Tree`] whose root has the given index.
   fn index_mut(&mut self, index: usize) -> &mut Self::Output {
     get_tree_mut(&mut self.nodes[index])
   }
 }
 
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<Grove<T, W>>
+  for Grove<U, W>
+{
+  fn eq(&self, g: &Grove<T, W>) -> bool {
+    self.nodes == g.nodes
+  }
+}
+
+impl<T: Eq, W: Eq> Eq for Grove<T, W> {}
+
+impl<T: Hash, W: Hash> Hash for Grove<T, W> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.nodes.hash(state)
+  }
+}
+
+/// Copies the nodes of a [`Grove`] into a new [`GroveBuf`].
+impl<T: Clone, W: Width> ToOwned for Grove<T, W> {
+  type Owned = GroveBuf<T, W>;
+
+  fn to_owned(&self) -> GroveBuf<T, W> {
+    GroveBuf {
+      nodes: self.nodes.to_vec(),
+      deleted: vec![],
+    }
+  }
+}
+
+impl<T: Clone, W: Width> From<&Grove<T, W>> for Box<Grove<T, W>> {
+  fn from(g: &Grove<T, W>) -> Box<Grove<T, W>> {
+    unsafe { box_as_grove_unchecked(g.nodes.into()) }
+  }
+}
+
+impl<T: Clone, W: Width> From<&Grove<T, W>> for Rc<Grove<T, W>> {
+  fn from(g: &Grove<T, W>) -> Rc<Grove<T, W>> {
+    unsafe { rc_as_grove_unchecked(g.nodes.into()) }
+  }
+}
+
+impl<T: Clone, W: Width> From<&Grove<T, W>> for Arc<Grove<T, W>> {
+  fn from(g: &Grove<T, W>) -> Arc<Grove<T, W>> {
+    unsafe { arc_as_grove_unchecked(g.nodes.into()) }
+  }
+}
+
+impl<T: Clone, W: Width> Clone for Box<Grove<T, W>> {
+  fn clone(&self) -> Box<Grove<T, W>> {
+    self.as_ref().into()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::grove_buf;
@@ -121,4 +353,92 @@ mod tests {
     let v: Vec<_> = g.as_ref().nodes(ReversePostorder).cloned().collect();
     assert_eq!(v, vec![7, 6, 5, 4, 3, 2, 1]);
   }
+
+  #[test]
+  fn scan_up_empty() {
+    let g = GroveBuf::<i32>::new();
+    assert!(g.as_ref().scan_up(|n, _| *n).is_empty());
+  }
+
+  #[test]
+  fn scan_up_children_in_order() {
+    let g = grove_buf![[1, 2] => 3, 4, [5, [6] => 7] => 8];
+    let s = g.as_ref().scan_up(|n, children: &[String]| {
+      format!("{}({})", n, children.join(","))
+    });
+    let v: Vec<_> = s.nodes(Preorder).cloned().collect();
+    assert_eq!(
+      v,
+      vec![
+        "1()",
+        "2()",
+        "3(1(),2())",
+        "4()",
+        "5()",
+        "6()",
+        "7(6())",
+        "8(5(),7(6()))"
+      ]
+    );
+    let widths: Vec<_> = s.trees(Preorder).map(|t| t.len()).collect();
+    assert_eq!(widths, vec![1, 1, 3, 1, 1, 1, 2, 4]);
+  }
+
+  #[test]
+  fn scan_down_empty() {
+    let g = GroveBuf::<i32>::new();
+    assert!(g.as_ref().scan_down(|n, _: Option<&i32>, _| *n).is_empty());
+  }
+
+  #[test]
+  fn scan_down_paths() {
+    let g = grove_buf![[1, 2] => 3, 4, [5, [6] => 7] => 8];
+    let s = g.as_ref().scan_down(|n, parent: Option<&String>, ordinal| {
+      match parent {
+        Some(p) => format!("{}/{}:{}", p, ordinal, n),
+        None => format!("{}:{}", ordinal, n),
+      }
+    });
+    let v: Vec<_> = s.nodes(Preorder).cloned().collect();
+    assert_eq!(
+      v,
+      vec![
+        "0:3/0:1",
+        "0:3/1:2",
+        "0:3",
+        "1:4",
+        "2:8/0:5",
+        "2:8/1:7/0:6",
+        "2:8/1:7",
+        "2:8"
+      ]
+    );
+    let widths: Vec<_> = s.trees(Preorder).map(|t| t.len()).collect();
+    assert_eq!(widths, vec![1, 1, 3, 1, 1, 1, 2, 4]);
+  }
+
+  #[test]
+  fn evaluate_empty() {
+    let g = GroveBuf::<i32>::new();
+    assert!(g.as_ref().evaluate(|n, _| *n).is_empty());
+  }
+
+  #[test]
+  fn evaluate_operands() {
+    let g = grove_buf![[1, 2] => 3, 4, [5, [6] => 7, 8] => 9];
+    let v = g.as_ref().evaluate(|n, operands| {
+      let arity = operands.len();
+      let operands: Vec<_> = operands.collect();
+      assert_eq!(operands.len(), arity);
+      format!("{}{:?}", n, operands)
+    });
+    assert_eq!(
+      v,
+      vec![
+        r#"3["1[]", "2[]"]"#,
+        "4[]",
+        r#"9["5[]", "7[\"6[]\"]", "8[]"]"#
+      ]
+    );
+  }
 }
@@ -1,26 +1,80 @@
 use crate::grove::Grove;
 use crate::internal;
 use crate::internal::as_grove_mut_unchecked;
+use crate::internal::arc_as_grove_unchecked;
 use crate::internal::as_grove_unchecked;
+use crate::internal::box_as_grove_unchecked;
+use crate::internal::rc_as_grove_unchecked;
 use crate::node::Node;
+use crate::node::Width;
+use crate::scan::Operands;
+use crate::shape::Shape;
+use crate::shape::ShapeMismatch;
+use crate::traversal::Either;
 use crate::traversal::TraversalOrder;
 use crate::tree::Tree;
+use crate::visitor;
+use crate::visitor::Visitor;
+use crate::visitor::VisitorMut;
+use std::borrow::Borrow;
+use std::borrow::BorrowMut;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// A sequence of trees structured so that nodes can be efficiently visited in
 /// pre-order or reverse post-order. For any node, its children can also be
 /// efficiently visited. All nodes are stored within a single allocation.
-/// The structure is append-only, so once a subtree has been formed, one can
-/// no longer modify it. In particular, this means that all children must be
-/// appended before a (sub)tree's root.
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct GroveBuf<T> {
-  pub(crate) nodes: Vec<Node<T>>,
+/// Trees are constructed by appending, so all children must be appended before
+/// a (sub)tree's root. Existing subtrees can also be modified structurally
+/// (e.g., with [`remove_tree`][GroveBuf::remove_tree]), but doing so requires
+/// shifting every subsequent node in the allocation.
+///
+/// The width of each subtree is stored as a `W`, which defaults to `usize`.
+/// Groves whose subtrees never exceed `u32::MAX` (or `u16::MAX`) nodes can use
+/// `GroveBuf<T, u32>` (or `GroveBuf<T, u16>`) to reduce their memory footprint.
+/// Appending a root whose subtree does not fit in `W` panics.
+///
+/// ```
+/// # use grove::*;
+/// let mut g: GroveBuf<i32, u32> = GroveBuf::default();
+/// g.builder().open().push(1).push(2).close(3).build();
+/// assert_eq!(g[2].len(), 3);
+/// ```
+#[derive(Clone)]
+pub struct GroveBuf<T, W = usize> {
+  pub(crate) nodes: Vec<Node<T, W>>,
+  // Whether each node has been marked deleted by `mark_deleted`. Empty unless
+  // some node has been marked, so that traversals need not consult it.
+  pub(crate) deleted: Vec<bool>,
 }
 
 /// Constructs a [`GroveBuf`] containing no trees.
-impl<T> Default for GroveBuf<T> {
-  fn default() -> GroveBuf<T> {
-    GroveBuf { nodes: vec![] }
+impl<T, W> Default for GroveBuf<T, W> {
+  fn default() -> GroveBuf<T, W> {
+    GroveBuf {
+      nodes: vec![],
+      deleted: vec![],
+    }
+  }
+}
+
+/// Compares the nodes of both groves, including those marked deleted; see
+/// [`mark_deleted`][GroveBuf::mark_deleted].
+impl<T: PartialEq, W: PartialEq> PartialEq for GroveBuf<T, W> {
+  fn eq(&self, g: &GroveBuf<T, W>) -> bool {
+    self.nodes == g.nodes
+  }
+}
+
+impl<T: Eq, W: Eq> Eq for GroveBuf<T, W> {}
+
+/// Hashes the nodes of the grove as [`Grove`] does, including those marked
+/// deleted.
+impl<T: Hash, W: Hash> Hash for GroveBuf<T, W> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.nodes.hash(state);
   }
 }
 
@@ -49,14 +103,14 @@ impl<T> Default for GroveBuf<T> {
 ///
 /// With this last consideration it is, a valid these rules ensure at
 /// compile-time that a valid [`GroveBuf`] is always properly constructed.
-pub struct GroveBufBuilder<'a, T, N: internal::Internal>(
-  &'a mut GroveBuf<T>,
+pub struct GroveBufBuilder<'a, T, N: internal::Internal, W = usize>(
+  &'a mut GroveBuf<T, W>,
   N,
 );
 
-impl<'a, T, N: internal::Internal> GroveBufBuilder<'a, T, N> {
+impl<'a, T, N: internal::Internal, W: Width> GroveBufBuilder<'a, T, N, W> {
   /// Add a new leaf in the [`GroveBuf`] at the appropriate depth.
-  pub fn push(self, value: T) -> GroveBufBuilder<'a, T, N> {
+  pub fn push(self, value: T) -> GroveBufBuilder<'a, T, N, W> {
     self.0.push(value);
     self
   }
@@ -65,18 +119,24 @@ impl<'a, T, N: internal::Internal> GroveBufBuilder<'a, T, N> {
   /// corresponding call to [`close`][GroveBufBuilder::close] to match this call
   /// to [`open`][GroveBufBuilder::open]. Failure to do so will result in a
   /// compilation failure.
-  pub fn open(self) -> GroveBufBuilder<'a, T, internal::Succ<N>> {
+  pub fn open(self) -> GroveBufBuilder<'a, T, internal::Succ<N>, W> {
     let len = self.0.len();
     GroveBufBuilder(self.0, internal::Succ { stashed: self.1, position: len })
   }
 }
 
-impl<'a, T, N: internal::Internal> GroveBufBuilder<'a, T, internal::Succ<N>> {
+impl<'a, T, N: internal::Internal, W: Width>
+  GroveBufBuilder<'a, T, internal::Succ<N>, W>
+{
   /// Consumes `self` and returns a [`GroveBufBuilder`] of depth one smaller. than
   /// that of `self`. Adds a new tree to the referenced [`GroveBuf`] whose children
   /// consist of those nodes and subtrees constructed since the corresponding
   /// call to [`open`][GroveBufBuilder::open].
-  pub fn close(self, value: T) -> GroveBufBuilder<'a, T, N> {
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of the new tree is not representable by `W`.
+  pub fn close(self, value: T) -> GroveBufBuilder<'a, T, N, W> {
     unsafe {
       self.0.push_unchecked(value, self.1.position);
     }
@@ -84,10 +144,10 @@ impl<'a, T, N: internal::Internal> GroveBufBuilder<'a, T, internal::Succ<N>> {
   }
 }
 
-impl<'a, T> GroveBufBuilder<'a, T, internal::Zero> {
+impl<'a, T, W> GroveBufBuilder<'a, T, internal::Zero, W> {
   /// Consumes `self`, returning a mutable reference to the underlying
   /// [`GroveBuf`].
-  pub fn build(self) -> &'a mut GroveBuf<T> {
+  pub fn build(self) -> &'a mut GroveBuf<T, W> {
     self.0
   }
 }
@@ -97,7 +157,9 @@ impl<T> GroveBuf<T> {
   pub fn new() -> GroveBuf<T> {
     Default::default()
   }
+}
 
+impl<T, W: Width> GroveBuf<T, W> {
   /// Returns `true` if and only if the [`GroveBuf`] contains no trees.
   pub fn is_empty(&self) -> bool {
     self.nodes.is_empty()
@@ -109,22 +171,29 @@ impl<T> GroveBuf<T> {
   }
 
   /// Returns a `&Grove<T>` referring to `&self`.
-  pub fn as_ref(&self) -> &Grove<T> {
+  #[allow(clippy::should_implement_trait)]
+  pub fn as_ref(&self) -> &Grove<T, W> {
     unsafe { as_grove_unchecked(&self.nodes) }
   }
 
   /// Returns a `&mut Grove<T>` referring to `&mut self`.
-  pub fn as_mut(&mut self) -> &mut Grove<T> {
+  #[allow(clippy::should_implement_trait)]
+  pub fn as_mut(&mut self) -> &mut Grove<T, W> {
     unsafe { as_grove_mut_unchecked(&mut self.nodes) }
   }
 
   /// Returns an iterator over references to the nodes in the grove according
-  /// to the specified treversal `order`.
+  /// to the specified treversal `order`. Skips nodes marked deleted; see
+  /// [`mark_deleted`][GroveBuf::mark_deleted].
   pub fn nodes<Order: TraversalOrder>(
     &self,
     order: Order,
   ) -> impl std::iter::Iterator<Item = &T> {
-    self.as_ref().nodes(order)
+    if self.deleted.is_empty() {
+      Either::Left(order.node_iter(&self.nodes))
+    } else {
+      Either::Right(order.live_node_iter(&self.nodes, &self.deleted))
+    }
   }
 
   /// Returns an iterator over mutable references to the nodes in the grove
@@ -133,7 +202,11 @@ impl<T> GroveBuf<T> {
     &mut self,
     order: Order,
   ) -> impl std::iter::Iterator<Item = &mut T> {
-    self.as_mut().nodes_mut(order)
+    if self.deleted.is_empty() {
+      Either::Left(order.node_iter_mut(&mut self.nodes))
+    } else {
+      Either::Right(order.live_node_iter_mut(&mut self.nodes, &self.deleted))
+    }
   }
 
   /// Returns an iterator over references to the trees in the grove according
@@ -158,11 +231,18 @@ impl<T> GroveBuf<T> {
   /// grove_buf![8];
   /// ```
   ///
+  /// Skips the subtrees rooted at nodes marked deleted, though the trees it
+  /// yields still include any deleted descendants; see
+  /// [`mark_deleted`][GroveBuf::mark_deleted].
   pub fn trees<Order: TraversalOrder>(
     &self,
     order: Order,
-  ) -> impl std::iter::Iterator<Item = &Tree<T>> {
-    self.as_ref().trees(order)
+  ) -> impl std::iter::Iterator<Item = &Tree<T, W>> {
+    if self.deleted.is_empty() {
+      Either::Left(order.tree_iter(&self.nodes))
+    } else {
+      Either::Right(order.live_tree_iter(&self.nodes, &self.deleted))
+    }
   }
 
   /// Returns an iterator over mutable references to the trees in the grove
@@ -170,16 +250,210 @@ impl<T> GroveBuf<T> {
   pub fn trees_mut<Order: TraversalOrder>(
     &mut self,
     order: Order,
-  ) -> impl std::iter::Iterator<Item = &mut Tree<T>> {
-    self.as_mut().trees_mut(order)
+  ) -> impl std::iter::Iterator<Item = &mut Tree<T, W>> {
+    if self.deleted.is_empty() {
+      Either::Left(order.tree_iter_mut(&mut self.nodes))
+    } else {
+      Either::Right(order.live_tree_iter_mut(&mut self.nodes, &self.deleted))
+    }
+  }
+
+  /// Traverses each tree in the grove with `visitor`. See [`Grove::visit`].
+  pub fn visit<V: Visitor<T, W> + ?Sized>(&self, visitor: &mut V) {
+    if self.deleted.is_empty() {
+      self.as_ref().visit(visitor)
+    } else {
+      visitor::visit(&self.nodes, |i| !self.deleted[i], visitor)
+    }
+  }
+
+  /// Traverses each tree in the grove with `visitor`, providing mutable
+  /// references. See [`Grove::visit_mut`].
+  pub fn visit_mut<V: VisitorMut<T, W> + ?Sized>(&mut self, visitor: &mut V) {
+    if self.deleted.is_empty() {
+      self.as_mut().visit_mut(visitor)
+    } else {
+      let deleted = &self.deleted;
+      visitor::visit_mut(&mut self.nodes, |i| !deleted[i], visitor)
+    }
+  }
+
+  /// Computes a value for every node from its own value and the values
+  /// computed for its children. See [`Grove::scan_up`].
+  pub fn scan_up<U>(&self, f: impl FnMut(&T, &[U]) -> U) -> GroveBuf<U, W> {
+    self.with_deleted(self.as_ref().scan_up(f))
+  }
+
+  /// Computes a value for every node from its own value, the value computed
+  /// for its parent, and its ordinal among its siblings. See
+  /// [`Grove::scan_down`].
+  pub fn scan_down<U>(
+    &self,
+    f: impl FnMut(&T, Option<&U>, usize) -> U,
+  ) -> GroveBuf<U, W> {
+    self.with_deleted(self.as_ref().scan_down(f))
+  }
+
+  /// Evaluates the grove as postfix code, returning one result per top-level
+  /// tree. See [`Grove::evaluate`].
+  pub fn evaluate<U>(
+    &self,
+    f: impl FnMut(&T, Operands<'_, U>) -> U,
+  ) -> Vec<U> {
+    self.as_ref().evaluate(f)
+  }
+
+  /// Consumes `self`, returning a [`GroveBuf`] with the same shape whose
+  /// values are obtained by applying `f` to each value in `self`.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+  /// assert_eq!(g.map(|n| n * 10), grove_buf![[10, 20] => 30, 40]);
+  /// ```
+  pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> GroveBuf<U, W> {
+    GroveBuf {
+      nodes: self
+        .nodes
+        .into_iter()
+        .map(|node| Node {
+          value: f(node.value),
+          width: node.width,
+        })
+        .collect(),
+      deleted: self.deleted,
+    }
+  }
+
+  /// Analogous to [`map`][GroveBuf::map], but `f` may fail. Values are mapped
+  /// in [`Preorder`][crate::Preorder] and the first error encountered is
+  /// returned.
+  pub fn try_map<U, E>(
+    self,
+    mut f: impl FnMut(T) -> Result<U, E>,
+  ) -> Result<GroveBuf<U, W>, E> {
+    Ok(GroveBuf {
+      nodes: self
+        .nodes
+        .into_iter()
+        .map(|node| {
+          Ok(Node {
+            value: f(node.value)?,
+            width: node.width,
+          })
+        })
+        .collect::<Result<_, E>>()?,
+      deleted: self.deleted,
+    })
+  }
+
+  /// Returns a [`GroveBuf`] with the same shape as `self` whose values are
+  /// obtained by applying `f` to each value in `self`. See [`Grove::map_ref`].
+  pub fn map_ref<U>(&self, f: impl FnMut(&T) -> U) -> GroveBuf<U, W> {
+    self.with_deleted(self.as_ref().map_ref(f))
+  }
+
+  // Returns `g`, which must have as many nodes as `self`, with the nodes
+  // marked deleted in `self` marked deleted.
+  fn with_deleted<U>(&self, g: GroveBuf<U, W>) -> GroveBuf<U, W> {
+    GroveBuf {
+      deleted: self.deleted.clone(),
+      ..g
+    }
+  }
+
+  /// Returns `true` if and only if `self` and `other` have the same shape,
+  /// regardless of the values they hold. See [`Grove::same_shape`].
+  pub fn same_shape<U>(&self, other: &GroveBuf<U, W>) -> bool {
+    self.as_ref().same_shape(other.as_ref())
+  }
+
+  /// Returns the [`Shape`] of `self`, discarding all values.
+  pub fn shape(&self) -> Shape<W> {
+    self.as_ref().shape()
+  }
+
+  /// Converts `self` into a `Box<Grove<T, W>>`, dropping any excess capacity.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+  /// let boxed = g.into_boxed_grove();
+  /// assert_eq!(boxed.len(), 4);
+  /// assert_eq!(GroveBuf::from(boxed), grove_buf![[1, 2] => 3, 4]);
+  /// ```
+  pub fn into_boxed_grove(self) -> Box<Grove<T, W>> {
+    unsafe { box_as_grove_unchecked(self.into_nodes().into_boxed_slice()) }
+  }
+
+  /// Consumes `self`, separating its [`Shape`] from its values. The values
+  /// are returned in [`Preorder`][crate::Preorder]. Nodes marked deleted are
+  /// removed first, as by [`compact`][GroveBuf::compact].
+  pub fn into_parts(self) -> (Shape<W>, Vec<T>) {
+    let (widths, values) = self
+      .into_nodes()
+      .into_iter()
+      .map(|node| (node.width, node.value))
+      .unzip();
+    (Shape { widths }, values)
+  }
+
+  /// Combines `self` and `other` into a single [`GroveBuf`] of pairs of
+  /// corresponding values. Returns [`ShapeMismatch`] if `self` and `other` do
+  /// not have identical shapes. A node of the result is marked deleted if the
+  /// corresponding node of either `self` or `other` is.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<i32> = grove_buf![[1, 2] => 3];
+  /// let h: GroveBuf<char> = grove_buf![['a', 'b'] => 'c'];
+  /// assert_eq!(
+  ///   g.zip(h),
+  ///   Ok(grove_buf![[(1, 'a'), (2, 'b')] => (3, 'c')])
+  /// );
+  /// ```
+  pub fn zip<U>(
+    self,
+    other: GroveBuf<U, W>,
+  ) -> Result<GroveBuf<(T, U), W>, ShapeMismatch> {
+    if !self.same_shape(&other) {
+      return Err(ShapeMismatch);
+    }
+    let deleted = if other.deleted.is_empty() {
+      self.deleted
+    } else if self.deleted.is_empty() {
+      other.deleted
+    } else {
+      self.deleted.iter().zip(&other.deleted).map(|(l, r)| l | r).collect()
+    };
+    Ok(GroveBuf {
+      nodes: self
+        .nodes
+        .into_iter()
+        .zip(other.nodes)
+        .map(|(l, r)| Node {
+          value: (l.value, r.value),
+          width: l.width,
+        })
+        .collect(),
+      deleted,
+    })
   }
 
   /// Appends a leaf with value `value` to the grove.
   pub fn push(&mut self, value: T) {
-    self.nodes.push(Node { value, width: 1 });
+    self.nodes.push(Node::new(value, 1));
+    self.extend_deleted();
   }
 
   /// Appends a node with value `value` that contains `children` child nodes.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of the new tree is not representable by `W`.
   pub fn push_root(&mut self, value: T, children: usize) {
     let mut element = self.nodes.len();
     for _ in 0..children {
@@ -190,9 +464,64 @@ impl<T> GroveBuf<T> {
     }
   }
 
+  /// Moves all trees of `other` to the end of `self`, leaving `other` empty.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![[1] => 2];
+  /// let mut h = grove_buf![3, [4] => 5];
+  /// g.append(&mut h);
+  /// assert_eq!(g, grove_buf![[1] => 2, 3, [4] => 5]);
+  /// assert!(h.is_empty());
+  /// ```
+  pub fn append(&mut self, other: &mut GroveBuf<T, W>) {
+    if !other.deleted.is_empty() {
+      self.deleted.resize(self.nodes.len(), false);
+      self.deleted.append(&mut other.deleted);
+    }
+    self.nodes.append(&mut other.nodes);
+    self.extend_deleted();
+  }
+
+  /// Appends a copy of each tree in `grove` to the end of `self`.
+  pub fn extend_from_grove(&mut self, grove: &Grove<T, W>)
+  where
+    T: Clone,
+  {
+    self.nodes.extend_from_slice(&grove.nodes);
+    self.extend_deleted();
+  }
+
+  /// Appends a node with value `value` whose children are the trees of
+  /// `children`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of the new tree is not representable by `W`, leaving
+  /// `self` unchanged.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let mut g: GroveBuf<i32> = grove_buf![1];
+  /// g.push_tree_with_children(5, grove_buf![2, [3] => 4]);
+  /// assert_eq!(g, grove_buf![1, [2, [3] => 4] => 5]);
+  /// ```
+  pub fn push_tree_with_children(
+    &mut self,
+    value: T,
+    mut children: GroveBuf<T, W>,
+  ) {
+    let node = Node::new(value, children.nodes.len() + 1);
+    self.append(&mut children);
+    self.nodes.push(node);
+    self.extend_deleted();
+  }
+
   /// Constructs a new `GroveBufBuilder` from which one can safely push nodes
   /// into the [`GroveBuf`]
-  pub fn builder(&mut self) -> GroveBufBuilder<'_, T, internal::Zero> {
+  pub fn builder(&mut self) -> GroveBufBuilder<'_, T, internal::Zero, W> {
     GroveBufBuilder(self, internal::Zero)
   }
 
@@ -201,28 +530,100 @@ impl<T> GroveBuf<T> {
   /// to ensure that no elements are with index smaller than `position` are
   /// already contained in a subtree whose root is greater than or equal to
   /// `position`.
+  ///
+  /// # Safety
+  ///
+  /// `position` must be at most `self.len()` and must be the index of the
+  /// first node of a tree in the [`GroveBuf`] (or equal to `self.len()`).
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of the new tree is not representable by `W`.
   pub unsafe fn push_unchecked(&mut self, value: T, position: usize) {
-    self.nodes.push(Node {
-      value,
-      width: self.nodes.len() - position + 1,
-    });
+    self.nodes.push(Node::new(value, self.nodes.len() - position + 1));
+    self.extend_deleted();
+  }
+
+  // Records that the nodes appended since the deletion marks were last updated
+  // are not marked deleted.
+  pub(crate) fn extend_deleted(&mut self) {
+    if !self.deleted.is_empty() {
+      self.deleted.resize(self.nodes.len(), false);
+    }
+  }
+
+  // Consumes `self`, returning its nodes once those marked deleted have been
+  // removed.
+  fn into_nodes(mut self) -> Vec<Node<T, W>> {
+    self.compact();
+    self.nodes
   }
 }
 
-impl<'a, T> From<&'a GroveBuf<T>> for &'a Grove<T> {
-  fn from(g: &'a GroveBuf<T>) -> &'a Grove<T> {
+impl<'a, T, W: Width> From<&'a GroveBuf<T, W>> for &'a Grove<T, W> {
+  fn from(g: &'a GroveBuf<T, W>) -> &'a Grove<T, W> {
     g.as_ref()
   }
 }
 
-impl<'a, T> From<&'a mut GroveBuf<T>> for &'a mut Grove<T> {
-  fn from(g: &'a mut GroveBuf<T>) -> &'a mut Grove<T> {
+impl<'a, T, W: Width> From<&'a mut GroveBuf<T, W>>
+  for &'a mut Grove<T, W>
+{
+  fn from(g: &'a mut GroveBuf<T, W>) -> &'a mut Grove<T, W> {
     g.as_mut()
   }
 }
 
-impl<T> std::ops::Index<usize> for GroveBuf<T> {
-  type Output = Tree<T>;
+impl<T, W: Width> Borrow<Grove<T, W>> for GroveBuf<T, W> {
+  fn borrow(&self) -> &Grove<T, W> {
+    self.as_ref()
+  }
+}
+
+impl<T, W: Width> BorrowMut<Grove<T, W>> for GroveBuf<T, W> {
+  fn borrow_mut(&mut self) -> &mut Grove<T, W> {
+    self.as_mut()
+  }
+}
+
+/// Copies the nodes of a [`Tree`] into a new [`GroveBuf`] holding just that
+/// tree.
+impl<T: Clone, W: Width> From<&Tree<T, W>> for GroveBuf<T, W> {
+  fn from(t: &Tree<T, W>) -> GroveBuf<T, W> {
+    t.as_grove().to_owned()
+  }
+}
+
+impl<T, W: Width> From<GroveBuf<T, W>> for Box<Grove<T, W>> {
+  fn from(g: GroveBuf<T, W>) -> Box<Grove<T, W>> {
+    g.into_boxed_grove()
+  }
+}
+
+impl<T, W: Width> From<GroveBuf<T, W>> for Rc<Grove<T, W>> {
+  fn from(g: GroveBuf<T, W>) -> Rc<Grove<T, W>> {
+    unsafe { rc_as_grove_unchecked(g.into_nodes().into()) }
+  }
+}
+
+impl<T, W: Width> From<GroveBuf<T, W>> for Arc<Grove<T, W>> {
+  fn from(g: GroveBuf<T, W>) -> Arc<Grove<T, W>> {
+    unsafe { arc_as_grove_unchecked(g.into_nodes().into()) }
+  }
+}
+
+impl<T, W: Width> From<Box<Grove<T, W>>> for GroveBuf<T, W> {
+  fn from(g: Box<Grove<T, W>>) -> GroveBuf<T, W> {
+    let nodes = unsafe { Box::from_raw(Box::into_raw(g) as *mut [Node<T, W>]) };
+    GroveBuf {
+      nodes: nodes.into_vec(),
+      deleted: vec![],
+    }
+  }
+}
+
+impl<T, W: Width> std::ops::Index<usize> for GroveBuf<T, W> {
+  type Output = Tree<T, W>;
 
   /// Returns a reference to the tree whose root has the given index.
   fn index(&self, index: usize) -> &Self::Output {
@@ -230,7 +631,7 @@ impl<T> std::ops::Index<usize> for GroveBuf<T> {
   }
 }
 
-impl<T> std::ops::IndexMut<usize> for GroveBuf<T> {
+impl<T, W: Width> std::ops::IndexMut<usize> for GroveBuf<T, W> {
   /// Returns a reference to the tree whose root has the given index.
   fn index_mut(&mut self, index: usize) -> &mut Self::Output {
     &mut self.as_mut()[index]
@@ -266,9 +667,28 @@ macro_rules! grove_buf_impl {
 /// represents a tree whose root contains the value `root`, and whose children
 /// are `subtrees`. Each such subtree must recursively have one of the syntactic
 /// forms described above.
+///
+/// The result stores its subtree widths as `usize`. To use a narrower
+/// [`Width`][crate::Width] `W`, begin the macro with `@width W;`.
+///
+/// # Example
+/// ```
+/// # use grove::*;
+/// let g = grove_buf![@width u8; [1, 2] => 3, 4];
+/// let h: GroveBuf<i32, u8> = g.clone();
+/// assert_eq!(h.len(), 4);
+/// assert_eq!(grove_buf![@width u8;], GroveBuf::<i32, u8>::default());
+/// ```
 #[macro_export]
 macro_rules! grove_buf {
-  () => { GroveBuf::new() };
+  (@width $w:ty $(;)?) => { $crate::GroveBuf::<_, $w>::default() };
+  (@width $w:ty; $($trees:tt)*) => {{
+    let mut g = $crate::GroveBuf::<_, $w>::default();
+    let builder = g.builder();
+    let builder = $crate::grove_buf_impl![builder; $($trees)*];
+    std::mem::take(builder.build())
+  }};
+  () => { $crate::GroveBuf::new() };
   ([$($children:tt)*] => $root:expr, $($rest:tt)*) => {{
     let mut g = $crate::GroveBuf::new();
     let builder = g.builder();
@@ -297,26 +717,34 @@ macro_rules! grove_buf {
   }};
 }
 
-impl<T, U: PartialEq<T>> PartialEq<Tree<T>> for GroveBuf<U> {
-  fn eq(&self, t: &Tree<T>) -> bool {
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<Tree<T, W>>
+  for GroveBuf<U, W>
+{
+  fn eq(&self, t: &Tree<T, W>) -> bool {
     self.nodes == t.nodes
   }
 }
 
-impl<T, U: PartialEq<T>> PartialEq<GroveBuf<T>> for Tree<U> {
-  fn eq(&self, t: &GroveBuf<T>) -> bool {
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<GroveBuf<T, W>>
+  for Tree<U, W>
+{
+  fn eq(&self, t: &GroveBuf<T, W>) -> bool {
     self.nodes == t.nodes
   }
 }
 
-impl<T, U: PartialEq<T>> PartialEq<Grove<T>> for GroveBuf<U> {
-  fn eq(&self, g: &Grove<T>) -> bool {
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<Grove<T, W>>
+  for GroveBuf<U, W>
+{
+  fn eq(&self, g: &Grove<T, W>) -> bool {
     self.nodes == g.nodes
   }
 }
 
-impl<T, U: PartialEq<T>> PartialEq<GroveBuf<T>> for Grove<U> {
-  fn eq(&self, g: &GroveBuf<T>) -> bool {
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<GroveBuf<T, W>>
+  for Grove<U, W>
+{
+  fn eq(&self, g: &GroveBuf<T, W>) -> bool {
     self.nodes == g.nodes
   }
 }
@@ -598,8 +1026,156 @@ mod tests {
   #[test]
   fn index() {
     let g = complex_example();
-    assert_eq!(g[0], grove_buf![1 as i32]);
-    assert_eq!(g[1], grove_buf![2 as i32]);
-    assert_eq!(g[2], grove_buf![[1 as i32, 2] => 3]);
+    assert_eq!(g[0], grove_buf![1i32]);
+    assert_eq!(g[1], grove_buf![2i32]);
+    assert_eq!(g[2], grove_buf![[1i32, 2] => 3]);
+  }
+
+  #[test]
+  fn map() {
+    let g = complex_example().map(|n| n.to_string());
+    let nodes: Vec<_> = g.nodes(Preorder).map(String::as_str).collect();
+    assert_eq!(
+      nodes,
+      vec![
+        "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13",
+        "14", "15", "16"
+      ]
+    );
+
+    let widths: Vec<_> = g.trees(Preorder).map(Tree::len).collect();
+    assert_eq!(widths, vec![1, 1, 3, 1, 1, 3, 7, 1, 1, 1, 3, 1, 1, 3, 7, 16]);
+  }
+
+  #[test]
+  fn try_map() {
+    let g = grove_buf![[1, 2] => 3, 4];
+    assert_eq!(
+      g.clone().try_map(u8::try_from),
+      Ok(grove_buf![[1u8, 2] => 3, 4])
+    );
+    assert_eq!(
+      g.try_map(|n| if n % 2 == 0 { Err(n) } else { Ok(n) }),
+      Err(2)
+    );
+  }
+
+  #[test]
+  fn map_ref() {
+    let g = complex_example();
+    assert_eq!(g.map_ref(|n| *n), g);
+  }
+
+  #[test]
+  fn zip() {
+    let g = grove_buf![[1, 2] => 3, 4];
+    let h = grove_buf![[5, 6] => 7, 8];
+    assert_eq!(
+      g.clone().zip(h),
+      Ok(grove_buf![[(1, 5), (2, 6)] => (3, 7), (4, 8)])
+    );
+    assert_eq!(g.clone().zip(grove_buf![1, 2, 3, 4]), Err(ShapeMismatch));
+    assert_eq!(g.zip(grove_buf![[1, 2] => 3]), Err(ShapeMismatch));
+  }
+
+  #[test]
+  fn append() {
+    let mut g = grove_buf![[1, 2] => 3];
+    let mut h = GroveBuf::new();
+    g.append(&mut h);
+    assert_eq!(g, grove_buf![[1, 2] => 3]);
+    g.extend_from_grove(grove_buf![[4] => 5, 6].as_ref());
+    assert_eq!(g, grove_buf![[1, 2] => 3, [4] => 5, 6]);
+    g.push_tree_with_children(7, GroveBuf::new());
+    g.push_tree_with_children(9, grove_buf![8]);
+    assert_eq!(g, grove_buf![[1, 2] => 3, [4] => 5, 6, 7, [8] => 9]);
+  }
+
+  #[test]
+  fn to_owned() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    let owned: GroveBuf<i32> = g[2].as_grove().to_owned();
+    assert_eq!(owned, grove_buf![[2] => 3]);
+    assert_eq!(GroveBuf::from(&g[3]), grove_buf![[1, [2] => 3] => 4]);
+
+    let cow = std::borrow::Cow::Borrowed(g.as_ref());
+    assert_eq!(cow.into_owned(), g);
+  }
+
+  #[test]
+  fn borrow_lookup() {
+    let mut set = std::collections::HashSet::new();
+    set.insert(grove_buf![[1, 2] => 3]);
+    set.insert(grove_buf![[1] => 3, 2]);
+    assert!(set.contains(grove_buf![[1, 2] => 3].as_ref()));
+    assert!(!set.contains(grove_buf![1, 2, 3].as_ref()));
+  }
+
+  #[test]
+  fn shared() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    let rc: Rc<Grove<i32>> = g.clone().into();
+    let arc: Arc<Grove<i32>> = g.clone().into();
+    assert!(g == *rc);
+    assert!(g == *arc);
+    let v: Vec<_> = rc.clone().nodes(Preorder).copied().collect();
+    assert_eq!(v, vec![1, 2, 3, 4, 5]);
+
+    let tree: Arc<Tree<i32>> = (&arc[3]).into();
+    let handle = std::thread::spawn(move || *tree.root());
+    assert_eq!(handle.join().unwrap(), 4);
+
+    let boxed: Box<Grove<i32>> = g.as_ref().into();
+    assert_eq!(GroveBuf::from(boxed.clone()), g);
+    let boxed: Box<Tree<i32>> = (&g[2]).into();
+    assert_eq!(*boxed.clone(), g[2]);
+  }
+
+  #[test]
+  fn narrow_width() {
+    let mut g: GroveBuf<i32, u8> = Default::default();
+    g.builder().open().push(1).push(2).close(3).push(4).build();
+    g.push_root(5, 2);
+    let nodes: Vec<_> = g.nodes(Preorder).collect();
+    assert_eq!(nodes, vec![&1, &2, &3, &4, &5]);
+
+    let widths: Vec<_> = g.trees(Preorder).map(Tree::len).collect();
+    assert_eq!(widths, vec![1, 1, 3, 1, 5]);
+    assert_eq!(g.map_ref(|n| n * 2)[2], g.clone().map(|n| n * 2)[2]);
+  }
+
+  #[test]
+  #[should_panic(expected = "subtree width overflow")]
+  fn push_root_overflow() {
+    let mut g: GroveBuf<i32, u8> = Default::default();
+    for i in 0..255 {
+      g.push(i);
+    }
+    g.push_root(255, 255);
+  }
+
+  #[test]
+  #[should_panic(expected = "subtree width overflow")]
+  fn close_overflow() {
+    let mut g: GroveBuf<i32, u8> = Default::default();
+    let mut builder = g.builder().open();
+    for i in 0..255 {
+      builder = builder.push(i);
+    }
+    builder.close(255);
+  }
+
+  #[test]
+  fn push_tree_with_children_overflow() {
+    let mut g = grove_buf![@width u8; 1];
+    let mut children = GroveBuf::default();
+    for i in 0..255 {
+      children.push(i);
+    }
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      g.push_tree_with_children(255, children)
+    }));
+    assert!(result.is_err());
+    assert_eq!(g, grove_buf![@width u8; 1]);
   }
 }
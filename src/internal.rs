@@ -2,7 +2,10 @@
 
 use crate::grove::Grove;
 use crate::node::Node;
+use crate::node::Width;
 use crate::tree::Tree;
+use std::rc::Rc;
+use std::sync::Arc;
 
 pub(crate) trait Internal {}
 
@@ -16,41 +19,97 @@ pub struct Succ<N: crate::internal::Internal> {
 impl Internal for Zero {}
 impl<N: Internal> Internal for Succ<N> {}
 
-pub(crate) unsafe fn as_tree_unchecked<T>(nodes: &[Node<T>]) -> &Tree<T> {
-  &*(nodes as *const [Node<T>] as *const Tree<T>)
+pub(crate) unsafe fn as_tree_unchecked<T, W>(
+  nodes: &[Node<T, W>],
+) -> &Tree<T, W> {
+  &*(nodes as *const [Node<T, W>] as *const Tree<T, W>)
 }
 
-pub(crate) fn get_tree<T>(node: &Node<T>) -> &Tree<T> {
+pub(crate) fn get_tree<T, W: Width>(node: &Node<T, W>) -> &Tree<T, W> {
   unsafe {
     let slice = std::slice::from_raw_parts(
-      (node as *const Node<T>).offset(-(node.width as isize) + 1),
-      node.width,
+      (node as *const Node<T, W>).offset(-(node.width() as isize) + 1),
+      node.width(),
     );
     as_tree_unchecked(slice)
   }
 }
 
-pub(crate) unsafe fn as_tree_mut_unchecked<T>(
-  slice: &mut [Node<T>],
-) -> &mut Tree<T> {
-  &mut *(slice as *mut [Node<T>] as *mut Tree<T>)
+pub(crate) unsafe fn as_tree_mut_unchecked<T, W>(
+  slice: &mut [Node<T, W>],
+) -> &mut Tree<T, W> {
+  &mut *(slice as *mut [Node<T, W>] as *mut Tree<T, W>)
 }
 
-pub(crate) fn get_tree_mut<T>(node: &mut Node<T>) -> &mut Tree<T> {
+pub(crate) fn get_tree_mut<T, W: Width>(
+  node: &mut Node<T, W>,
+) -> &mut Tree<T, W> {
   unsafe {
+    let width = node.width();
     as_tree_mut_unchecked(std::slice::from_raw_parts_mut(
-      (node as *mut Node<T>).offset(-(node.width as isize) + 1),
-      node.width,
+      (node as *mut Node<T, W>).offset(-(width as isize) + 1),
+      width,
     ))
   }
 }
 
-pub(crate) unsafe fn as_grove_unchecked<T>(slice: &[Node<T>]) -> &Grove<T> {
-  &*(slice as *const [Node<T>] as *const Grove<T>)
+pub(crate) unsafe fn as_grove_unchecked<T, W>(
+  slice: &[Node<T, W>],
+) -> &Grove<T, W> {
+  &*(slice as *const [Node<T, W>] as *const Grove<T, W>)
 }
 
-pub(crate) unsafe fn as_grove_mut_unchecked<T>(
-  slice: &mut [Node<T>],
-) -> &mut Grove<T> {
-  &mut *(slice as *mut [Node<T>] as *mut Grove<T>)
+pub(crate) unsafe fn as_grove_mut_unchecked<T, W>(
+  slice: &mut [Node<T, W>],
+) -> &mut Grove<T, W> {
+  &mut *(slice as *mut [Node<T, W>] as *mut Grove<T, W>)
+}
+
+pub(crate) unsafe fn box_as_grove_unchecked<T, W>(
+  nodes: Box<[Node<T, W>]>,
+) -> Box<Grove<T, W>> {
+  Box::from_raw(Box::into_raw(nodes) as *mut Grove<T, W>)
+}
+
+pub(crate) unsafe fn rc_as_grove_unchecked<T, W>(
+  nodes: Rc<[Node<T, W>]>,
+) -> Rc<Grove<T, W>> {
+  Rc::from_raw(Rc::into_raw(nodes) as *const Grove<T, W>)
+}
+
+pub(crate) unsafe fn arc_as_grove_unchecked<T, W>(
+  nodes: Arc<[Node<T, W>]>,
+) -> Arc<Grove<T, W>> {
+  Arc::from_raw(Arc::into_raw(nodes) as *const Grove<T, W>)
+}
+
+pub(crate) unsafe fn box_as_tree_unchecked<T, W>(
+  nodes: Box<[Node<T, W>]>,
+) -> Box<Tree<T, W>> {
+  Box::from_raw(Box::into_raw(nodes) as *mut Tree<T, W>)
+}
+
+pub(crate) unsafe fn rc_as_tree_unchecked<T, W>(
+  nodes: Rc<[Node<T, W>]>,
+) -> Rc<Tree<T, W>> {
+  Rc::from_raw(Rc::into_raw(nodes) as *const Tree<T, W>)
+}
+
+pub(crate) unsafe fn arc_as_tree_unchecked<T, W>(
+  nodes: Arc<[Node<T, W>]>,
+) -> Arc<Tree<T, W>> {
+  Arc::from_raw(Arc::into_raw(nodes) as *const Tree<T, W>)
+}
+
+/// Returns a single path of 100,000 nodes in which each node holds its
+/// distance from the leaf, for checking that operations on deep trees do not
+/// overflow the stack.
+#[cfg(test)]
+pub(crate) fn deep_tree() -> crate::grove_buf::GroveBuf<i32> {
+  let mut g = crate::grove_buf::GroveBuf::new();
+  g.push(0);
+  for i in 1..100_000 {
+    g.push_root(i, 1);
+  }
+  g
 }
@@ -1,17 +1,41 @@
 #![allow(private_bounds)]
+#![doc = include_str!("../README.md")]
 
-#[doc = include_str!("../README.md")]
-
+mod attribute;
+mod debug;
+mod edit;
 mod internal;
 mod grove;
 mod grove_buf;
 mod node;
+mod node_map;
+mod render;
+mod scan;
+mod shape;
+mod shared;
+mod split;
 mod traversal;
 mod tree;
+mod visitor;
 
+pub use attribute::AttributeGrove;
 pub use grove::Grove;
 pub use grove_buf::GroveBuf;
 pub use grove_buf::GroveBufBuilder;
+pub use node::Width;
+pub use node_map::NodeMap;
+pub use node_map::SparseNodeMap;
+pub use render::Render;
+pub use scan::Operands;
+pub use shape::Shape;
+pub use shape::ShapeMismatch;
+pub use shared::SharedTree;
+pub use split::SplitGrove;
+pub use split::SplitGroveBuf;
+pub use split::SplitTree;
+pub use split::SplitTreeMut;
 pub use traversal::Preorder;
 pub use traversal::ReversePostorder;
 pub use tree::Tree;
+pub use visitor::Visitor;
+pub use visitor::VisitorMut;
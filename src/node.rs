@@ -2,27 +2,113 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// An unsigned integer type used to store the width of each subtree. Groves
+/// whose subtrees never exceed a small number of nodes can use a narrower type
+/// than the default of `usize` to save memory.
+///
+/// This trait is sealed: it is implemented for `u8`, `u16`, `u32`, `u64` and
+/// `usize`, and cannot be implemented outside of this crate.
+///
+/// # Example
+/// ```
+/// # use grove::*;
+/// fn leaves<T, W: Width>(g: &Grove<T, W>) -> usize {
+///   g.trees(Preorder).filter(|t| t.len() == 1).count()
+/// }
+///
+/// let g = grove_buf![@width u8; [1, 2] => 3, 4];
+/// assert_eq!(leaves(g.as_ref()), 3);
+/// ```
+pub trait Width: sealed::Sealed + Copy + Debug + Eq + Hash {
+  /// Converts `n` to `Self`, returning `None` if `n` is not representable.
+  fn from_usize(n: usize) -> Option<Self>;
+
+  /// Converts `self` to a `usize`.
+  fn to_usize(self) -> usize;
+}
+
+mod sealed {
+  pub trait Sealed {}
+}
+
+macro_rules! impl_width {
+  ($($t:ty),*) => {$(
+    impl sealed::Sealed for $t {}
+
+    impl Width for $t {
+      fn from_usize(n: usize) -> Option<Self> {
+        <$t>::try_from(n).ok()
+      }
+
+      fn to_usize(self) -> usize {
+        self as usize
+      }
+    }
+  )*};
+}
+
+impl_width!(u8, u16, u32, u64, usize);
+
+/// Converts `n` to a `W`.
+///
+/// # Panics
+///
+/// Panics if `n` is not representable by `W`.
+pub(crate) fn to_width<W: Width>(n: usize) -> W {
+  W::from_usize(n).expect("subtree width overflow")
+}
 
 #[derive(Clone, Eq)]
-pub struct Node<T> {
+pub struct Node<T, W = usize> {
   pub(crate) value: T,
-  pub(crate) width: usize,
+  pub(crate) width: W,
+}
+
+impl<T, W: Width> Node<T, W> {
+  /// Constructs a [`Node`] whose subtree consists of `width` nodes.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `width` is not representable by `W`.
+  pub(crate) fn new(value: T, width: usize) -> Node<T, W> {
+    Node {
+      value,
+      width: to_width(width),
+    }
+  }
+
+  /// Returns the number of nodes in the subtree rooted at this node.
+  pub(crate) fn width(&self) -> usize {
+    self.width.to_usize()
+  }
 }
 
-impl<T: Debug> Debug for Node<T> {
+impl<T: Debug, W> Debug for Node<T, W> {
   fn fmt(&self, f: &mut Formatter) -> Result {
     self.value.fmt(f)
   }
 }
 
-impl<T: Display> Display for Node<T> {
+impl<T: Display, W> Display for Node<T, W> {
   fn fmt(&self, f: &mut Formatter) -> Result {
     self.value.fmt(f)
   }
 }
 
-impl<T, U: PartialEq<T>> PartialEq<Node<T>> for Node<U> {
-  fn eq(&self, n: &Node<T>) -> bool {
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<Node<T, W>>
+  for Node<U, W>
+{
+  fn eq(&self, n: &Node<T, W>) -> bool {
     self.value == n.value && self.width == n.width
   }
 }
+
+impl<T: Hash, W: Hash> Hash for Node<T, W> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.value.hash(state);
+    self.width.hash(state);
+  }
+}
@@ -0,0 +1,274 @@
+use crate::grove::Grove;
+use crate::node::Width;
+use crate::traversal::Preorder;
+use crate::traversal::TraversalOrder;
+use crate::tree::Tree;
+use std::collections::BTreeMap;
+
+/// A side table holding one value of type `V` for every node of a [`Grove`],
+/// indexed by the same positions used to index the [`Grove`]. This allows
+/// extra data to be attached to the nodes without rebuilding the [`Grove`].
+/// Structural modifications of the [`Grove`] are not reflected in the
+/// [`NodeMap`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct NodeMap<V> {
+  values: Vec<V>,
+}
+
+impl<V> NodeMap<V> {
+  /// Constructs a [`NodeMap`] with an entry for each node in `grove`, each
+  /// holding a clone of `value`.
+  pub fn new<T, W: Width>(grove: &Grove<T, W>, value: V) -> NodeMap<V>
+  where
+    V: Clone,
+  {
+    NodeMap {
+      values: vec![value; grove.len()],
+    }
+  }
+
+  /// Constructs a [`NodeMap`] with an entry for each node in `grove`, holding
+  /// the result of applying `f` to the tree rooted at that node. Trees are
+  /// visited in [`Preorder`].
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<i32> = grove_buf![[1, 2] => 3, 4];
+  /// let sizes = NodeMap::from_fn(g.as_ref(), |t| t.len());
+  /// assert_eq!(sizes[2], 3);
+  /// let v: Vec<_> = sizes.values(ReversePostorder).collect();
+  /// assert_eq!(v, vec![&1, &3, &1, &1]);
+  /// ```
+  pub fn from_fn<T, W: Width>(
+    grove: &Grove<T, W>,
+    f: impl FnMut(&Tree<T, W>) -> V,
+  ) -> NodeMap<V> {
+    NodeMap {
+      values: grove.trees(Preorder).map(f).collect(),
+    }
+  }
+
+  /// Returns `true` if and only if the [`NodeMap`] contains no entries.
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// Returns the number of entries in the [`NodeMap`].
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns a reference to the entry for the node at `index`, or `None` if
+  /// `index` is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&V> {
+    self.values.get(index)
+  }
+
+  /// Returns a mutable reference to the entry for the node at `index`, or
+  /// `None` if `index` is out of bounds.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut V> {
+    self.values.get_mut(index)
+  }
+
+  /// Returns an iterator over references to the entries according to the
+  /// specified traversal `order`.
+  pub fn values<Order: TraversalOrder>(
+    &self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &V> {
+    order.traverse(self.values.iter())
+  }
+
+  /// Returns an iterator over mutable references to the entries according to
+  /// the specified traversal `order`.
+  pub fn values_mut<Order: TraversalOrder>(
+    &mut self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &mut V> {
+    order.traverse(self.values.iter_mut())
+  }
+
+  /// Returns an iterator over the positions of the nodes paired with
+  /// references to their entries, according to the specified traversal
+  /// `order`.
+  pub fn iter<Order: TraversalOrder>(
+    &self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = (usize, &V)> {
+    order.traverse(self.values.iter().enumerate())
+  }
+}
+
+impl<V> std::ops::Index<usize> for NodeMap<V> {
+  type Output = V;
+
+  /// Returns a reference to the entry for the node at the given index.
+  fn index(&self, index: usize) -> &V {
+    &self.values[index]
+  }
+}
+
+impl<V> std::ops::IndexMut<usize> for NodeMap<V> {
+  /// Returns a mutable reference to the entry for the node at the given index.
+  fn index_mut(&mut self, index: usize) -> &mut V {
+    &mut self.values[index]
+  }
+}
+
+/// A side table holding values of type `V` for some of the nodes of a
+/// [`Grove`], indexed by the same positions used to index the [`Grove`].
+/// Analogous to [`NodeMap`], but suited to data present on only a few nodes.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SparseNodeMap<V> {
+  entries: BTreeMap<usize, V>,
+}
+
+/// Constructs a [`SparseNodeMap`] containing no entries.
+impl<V> Default for SparseNodeMap<V> {
+  fn default() -> SparseNodeMap<V> {
+    SparseNodeMap {
+      entries: BTreeMap::new(),
+    }
+  }
+}
+
+impl<V> SparseNodeMap<V> {
+  /// Constructs a [`SparseNodeMap`] containing no entries.
+  pub fn new() -> SparseNodeMap<V> {
+    Default::default()
+  }
+
+  /// Returns `true` if and only if the [`SparseNodeMap`] contains no entries.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Returns the number of entries in the [`SparseNodeMap`].
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Returns `true` if and only if the node at `index` has an entry.
+  pub fn contains(&self, index: usize) -> bool {
+    self.entries.contains_key(&index)
+  }
+
+  /// Sets the entry for the node at `index` to `value`, returning the previous
+  /// entry, if any.
+  pub fn insert(&mut self, index: usize, value: V) -> Option<V> {
+    self.entries.insert(index, value)
+  }
+
+  /// Removes and returns the entry for the node at `index`, if any.
+  pub fn remove(&mut self, index: usize) -> Option<V> {
+    self.entries.remove(&index)
+  }
+
+  /// Returns a reference to the entry for the node at `index`, if any.
+  pub fn get(&self, index: usize) -> Option<&V> {
+    self.entries.get(&index)
+  }
+
+  /// Returns a mutable reference to the entry for the node at `index`, if any.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut V> {
+    self.entries.get_mut(&index)
+  }
+
+  /// Returns an iterator over references to the entries according to the
+  /// specified traversal `order`.
+  pub fn values<Order: TraversalOrder>(
+    &self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &V> {
+    order.traverse(self.entries.values())
+  }
+
+  /// Returns an iterator over mutable references to the entries according to
+  /// the specified traversal `order`.
+  pub fn values_mut<Order: TraversalOrder>(
+    &mut self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &mut V> {
+    order.traverse(self.entries.values_mut())
+  }
+
+  /// Returns an iterator over the positions of the nodes with entries paired
+  /// with references to their entries, according to the specified traversal
+  /// `order`.
+  pub fn iter<Order: TraversalOrder>(
+    &self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = (usize, &V)> {
+    order.traverse(self.entries.iter()).map(|(&index, v)| (index, v))
+  }
+}
+
+impl<V> std::ops::Index<usize> for SparseNodeMap<V> {
+  type Output = V;
+
+  /// Returns a reference to the entry for the node at the given index.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the node at `index` has no entry.
+  fn index(&self, index: usize) -> &V {
+    &self.entries[&index]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grove_buf;
+  use crate::traversal::ReversePostorder;
+
+  #[test]
+  fn dense() {
+    let g = grove_buf![[1, 2] => 3, 4, [5] => 6];
+    let mut m = NodeMap::new(g.as_ref(), 0);
+    assert_eq!(m.len(), 6);
+    m[2] = 7;
+    *m.get_mut(5).unwrap() = 8;
+    assert_eq!(m.get(6), None);
+    let v: Vec<_> = m.iter(ReversePostorder).collect();
+    assert_eq!(
+      v,
+      vec![(5, &8), (4, &0), (3, &0), (2, &7), (1, &0), (0, &0)]
+    );
+    for value in m.values_mut(Preorder) {
+      *value += 1;
+    }
+    let v: Vec<_> = m.values(Preorder).copied().collect();
+    assert_eq!(v, vec![1, 1, 8, 1, 1, 9]);
+  }
+
+  #[test]
+  fn dense_from_fn() {
+    let g = grove_buf![[1, 2] => 3, 4, [5] => 6];
+    let m = NodeMap::from_fn(g.as_ref(), |t| *t.root() * 10);
+    let v: Vec<_> = m.values(Preorder).copied().collect();
+    assert_eq!(v, vec![10, 20, 30, 40, 50, 60]);
+  }
+
+  #[test]
+  fn sparse() {
+    let mut m = SparseNodeMap::new();
+    assert!(m.is_empty());
+    assert_eq!(m.insert(4, "d"), None);
+    assert_eq!(m.insert(1, "a"), None);
+    assert_eq!(m.insert(2, "b"), None);
+    assert_eq!(m.insert(4, "e"), Some("d"));
+    assert_eq!(m.len(), 3);
+    assert!(m.contains(2));
+    assert!(!m.contains(3));
+    assert_eq!(m[4], "e");
+    assert_eq!(m.remove(2), Some("b"));
+    assert_eq!(m.get(2), None);
+
+    let v: Vec<_> = m.iter(Preorder).collect();
+    assert_eq!(v, vec![(1, &"a"), (4, &"e")]);
+    let v: Vec<_> = m.values(ReversePostorder).collect();
+    assert_eq!(v, vec![&"e", &"a"]);
+  }
+}
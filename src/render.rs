@@ -0,0 +1,269 @@
+use crate::grove::Grove;
+use crate::grove_buf::GroveBuf;
+use crate::node::Node;
+use crate::node::Width;
+use crate::tree::Tree;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+/// A [`Display`]able rendering of a sequence of trees, drawing each tree below
+/// its root with one node per line and connectors leading from each node to
+/// its children. Constructed by [`Grove::render`] or [`Grove::render_with`]
+/// (or their analogues on [`Tree`] and [`GroveBuf`]), and configured with
+/// [`ascii`][Render::ascii] and [`max_depth`][Render::max_depth].
+///
+/// # Example
+/// ```
+/// # use grove::*;
+/// let g = grove_buf![["red", "blue"] => "color", ["left"] => "direction"];
+/// assert_eq!(
+///   g.render().to_string(),
+///   "color\n├── red\n└── blue\ndirection\n└── left"
+/// );
+/// ```
+pub struct Render<'a, T, F, W = usize> {
+  nodes: &'a [Node<T, W>],
+  format: F,
+  ascii: bool,
+  max_depth: Option<usize>,
+}
+
+impl<'a, T, F, W> Render<'a, T, F, W> {
+  /// Draws connectors with ASCII characters (`` |-- `` and `` `-- ``) rather
+  /// than box-drawing characters (`├──` and `└──`).
+  pub fn ascii(mut self) -> Self {
+    self.ascii = true;
+    self
+  }
+
+  /// Omits nodes more than `depth` levels below the roots of the top-level
+  /// trees. Nodes whose children are omitted are followed by an ellipsis.
+  pub fn max_depth(mut self, depth: usize) -> Self {
+    self.max_depth = Some(depth);
+    self
+  }
+}
+
+// Returns an iterator over the roots of the trees in `nodes[start..end]`, from
+// right to left.
+fn roots_rev<T, W: Width>(
+  nodes: &[Node<T, W>],
+  start: usize,
+  end: usize,
+) -> impl Iterator<Item = usize> + '_ {
+  let mut position = end;
+  std::iter::from_fn(move || {
+    (position > start).then(|| {
+      let root = position - 1;
+      position -= nodes[root].width();
+      root
+    })
+  })
+}
+
+impl<T, F: Fn(&T, &mut Formatter) -> Result, W: Width> Display
+  for Render<'_, T, F, W>
+{
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    let (branch, last_branch, guide, ellipsis) = if self.ascii {
+      ("|-- ", "`-- ", "|   ", " ...")
+    } else {
+      ("├── ", "└── ", "│   ", " …")
+    };
+    // Each entry holds the root of a tree, its depth, and whether it is the
+    // last of its siblings. The leftmost tree is at the top of the stack.
+    let mut stack: Vec<_> = roots_rev(self.nodes, 0, self.nodes.len())
+      .map(|root| (root, 0, false))
+      .collect();
+    // Holds, for each proper ancestor below the top level of the tree being
+    // drawn, whether it has siblings yet to be drawn.
+    let mut guides = vec![];
+    let mut first = true;
+    while let Some((root, depth, last)) = stack.pop() {
+      if !first {
+        f.write_str("\n")?;
+      }
+      first = false;
+      if depth > 0 {
+        guides.truncate(depth - 1);
+        for &more in &guides {
+          f.write_str(if more { guide } else { "    " })?;
+        }
+        f.write_str(if last { last_branch } else { branch })?;
+        guides.push(!last);
+      }
+      (self.format)(&self.nodes[root].value, f)?;
+
+      let start = root + 1 - self.nodes[root].width();
+      let mut children = roots_rev(self.nodes, start, root).peekable();
+      if children.peek().is_none() {
+        continue;
+      }
+      if self.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+        f.write_str(ellipsis)?;
+        continue;
+      }
+      let mut last = true;
+      for child in children {
+        stack.push((child, depth + 1, last));
+        last = false;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<T, W: Width> Grove<T, W> {
+  /// Returns a [`Render`] of the trees in `self`, formatting each value with
+  /// its [`Display`] implementation.
+  pub fn render(
+    &self,
+  ) -> Render<'_, T, impl Fn(&T, &mut Formatter) -> Result, W>
+  where
+    T: Display,
+  {
+    self.render_with(T::fmt)
+  }
+
+  /// Returns a [`Render`] of the trees in `self`, formatting each value with
+  /// `format`.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: GroveBuf<i32> = grove_buf![[[1] => 2, 3] => 4];
+  /// let r = g.as_ref().render_with(|n, f| write!(f, "#{}", n));
+  /// assert_eq!(r.ascii().max_depth(1).to_string(), "#4\n|-- #2 ...\n`-- #3");
+  /// ```
+  pub fn render_with<F: Fn(&T, &mut Formatter) -> Result>(
+    &self,
+    format: F,
+  ) -> Render<'_, T, F, W> {
+    Render {
+      nodes: &self.nodes,
+      format,
+      ascii: false,
+      max_depth: None,
+    }
+  }
+}
+
+impl<T, W: Width> Tree<T, W> {
+  /// Analogous to [`Grove::render`].
+  pub fn render(
+    &self,
+  ) -> Render<'_, T, impl Fn(&T, &mut Formatter) -> Result, W>
+  where
+    T: Display,
+  {
+    self.as_grove().render()
+  }
+
+  /// Analogous to [`Grove::render_with`].
+  pub fn render_with<F: Fn(&T, &mut Formatter) -> Result>(
+    &self,
+    format: F,
+  ) -> Render<'_, T, F, W> {
+    self.as_grove().render_with(format)
+  }
+}
+
+impl<T, W: Width> GroveBuf<T, W> {
+  /// Analogous to [`Grove::render`].
+  pub fn render(
+    &self,
+  ) -> Render<'_, T, impl Fn(&T, &mut Formatter) -> Result, W>
+  where
+    T: Display,
+  {
+    self.as_ref().render()
+  }
+
+  /// Analogous to [`Grove::render_with`].
+  pub fn render_with<F: Fn(&T, &mut Formatter) -> Result>(
+    &self,
+    format: F,
+  ) -> Render<'_, T, F, W> {
+    self.as_ref().render_with(format)
+  }
+}
+
+/// Draws the tree as by [`Tree::render`].
+impl<T: Display, W: Width> Display for Tree<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    self.render().fmt(f)
+  }
+}
+
+/// Draws the trees as by [`Grove::render`].
+impl<T: Display, W: Width> Display for Grove<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    self.render().fmt(f)
+  }
+}
+
+/// Draws the trees as by [`GroveBuf::render`].
+impl<T: Display, W: Width> Display for GroveBuf<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    self.render().fmt(f)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::grove_buf;
+  use crate::grove_buf::GroveBuf;
+
+  fn example() -> GroveBuf<i32> {
+    grove_buf![[[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9, [10] => 11]
+  }
+
+  #[test]
+  fn unicode() {
+    assert_eq!(
+      example().to_string(),
+      "9\n\
+       ├── 3\n\
+       │   ├── 1\n\
+       │   └── 2\n\
+       ├── 4\n\
+       └── 8\n    \
+           ├── 5\n    \
+           └── 7\n        \
+               └── 6\n\
+       11\n\
+       └── 10"
+    );
+    assert_eq!(example()[2].to_string(), "3\n├── 1\n└── 2");
+    assert_eq!(GroveBuf::<i32>::new().to_string(), "");
+  }
+
+  #[test]
+  fn ascii_with_depth_limit() {
+    assert_eq!(
+      example().render().ascii().max_depth(1).to_string(),
+      "9\n|-- 3 ...\n|-- 4\n`-- 8 ...\n11\n`-- 10"
+    );
+    assert_eq!(example().render().max_depth(0).to_string(), "9 …\n11 …");
+  }
+
+  #[test]
+  fn custom_format() {
+    let g = example();
+    let r = g[7].render_with(|n, f| write!(f, "<{:02}>", n));
+    assert_eq!(
+      r.to_string(),
+      "<08>\n├── <05>\n└── <07>\n    └── <06>"
+    );
+  }
+
+  #[test]
+  fn deep_tree() {
+    let g = crate::internal::deep_tree();
+    assert_eq!(
+      g.render().ascii().max_depth(2).to_string(),
+      "99999\n`-- 99998\n    `-- 99997 ..."
+    );
+  }
+}
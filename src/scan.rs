@@ -0,0 +1,130 @@
+// The scans over groves are implemented once for any layout in post-order,
+// given the number of nodes and the width of the subtree rooted at each node.
+// Nodes are passed to the callbacks by index, and the computed values are
+// returned in node order.
+
+/// An iterator over the results computed for the children of a node by
+/// [`Grove::evaluate`][crate::Grove::evaluate], from left to right.
+#[derive(Debug)]
+pub struct Operands<'a, U>(std::vec::Drain<'a, U>);
+
+impl<U> Iterator for Operands<'_, U> {
+  type Item = U;
+
+  fn next(&mut self) -> Option<U> {
+    self.0.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.0.size_hint()
+  }
+}
+
+impl<U> DoubleEndedIterator for Operands<'_, U> {
+  fn next_back(&mut self) -> Option<U> {
+    self.0.next_back()
+  }
+}
+
+impl<U> ExactSizeIterator for Operands<'_, U> {}
+
+// Returns the number of trees whose nodes are exactly those in `start..end`.
+fn count_trees(
+  width: &impl Fn(usize) -> usize,
+  start: usize,
+  end: usize,
+) -> usize {
+  let mut count = 0;
+  let mut position = end;
+  while position > start {
+    position -= width(position - 1);
+    count += 1;
+  }
+  count
+}
+
+pub(crate) fn scan_up<U>(
+  len: usize,
+  width: impl Fn(usize) -> usize,
+  mut f: impl FnMut(usize, &[U]) -> U,
+) -> Vec<U> {
+  let mut values: Vec<Option<U>> = Vec::with_capacity(len);
+  values.resize_with(len, || None);
+  // Values whose parents have not been reached yet, and the index of the node
+  // each was computed for.
+  let mut operands: Vec<U> = vec![];
+  let mut indices: Vec<usize> = vec![];
+  for i in 0..len {
+    let mut covered = 1;
+    let mut arity = 0;
+    while covered < width(i) {
+      covered += width(indices[indices.len() - 1 - arity]);
+      arity += 1;
+    }
+    let first = operands.len() - arity;
+    let value = f(i, &operands[first..]);
+    let children = indices.drain(first..).zip(operands.drain(first..));
+    for (index, operand) in children {
+      values[index] = Some(operand);
+    }
+    indices.push(i);
+    operands.push(value);
+  }
+  for (index, operand) in indices.into_iter().zip(operands) {
+    values[index] = Some(operand);
+  }
+  values.into_iter().map(Option::unwrap).collect()
+}
+
+pub(crate) fn scan_down<U>(
+  len: usize,
+  width: impl Fn(usize) -> usize,
+  mut f: impl FnMut(usize, Option<&U>, usize) -> U,
+) -> Vec<U> {
+  // Computed values are pushed in reverse order and reversed at the end.
+  let mut values: Vec<U> = Vec::with_capacity(len);
+  // Each entry holds the index of the first node of a subtree, the position
+  // of its root's computed value in `values`, and the number of its children
+  // not yet visited. The bottom entry represents the grove itself.
+  let mut stack: Vec<(usize, Option<usize>, usize)> =
+    vec![(0, None, count_trees(&width, 0, len))];
+  for i in (0..len).rev() {
+    while stack.last().unwrap().0 > i {
+      stack.pop();
+    }
+    let (_, parent, remaining) = stack.last_mut().unwrap();
+    *remaining -= 1;
+    let value = f(i, parent.map(|p| &values[p]), *remaining);
+    values.push(value);
+    if width(i) > 1 {
+      let start = i + 1 - width(i);
+      let children = count_trees(&width, start, i);
+      stack.push((start, Some(values.len() - 1), children));
+    }
+  }
+  values.reverse();
+  values
+}
+
+pub(crate) fn evaluate<U>(
+  len: usize,
+  width: impl Fn(usize) -> usize,
+  mut f: impl FnMut(usize, Operands<'_, U>) -> U,
+) -> Vec<U> {
+  // The width of the subtree which produced each operand on the stack.
+  let mut widths: Vec<usize> = vec![];
+  let mut operands = vec![];
+  for i in 0..len {
+    let mut covered = 1;
+    let mut arity = 0;
+    while covered < width(i) {
+      covered += widths[widths.len() - 1 - arity];
+      arity += 1;
+    }
+    widths.truncate(widths.len() - arity);
+    let value = f(i, Operands(operands.drain(operands.len() - arity..)));
+    widths.push(width(i));
+    operands.push(value);
+  }
+  operands
+}
@@ -0,0 +1,112 @@
+use crate::grove_buf::GroveBuf;
+use crate::node::Node;
+use crate::node::Width;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+/// The structure of a [`GroveBuf`][crate::GroveBuf] without any of its values.
+/// A [`Shape`] can be obtained from [`Grove::shape`][crate::Grove::shape] and
+/// reused to construct groves of the same shape holding different values.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Shape<W = usize> {
+  pub(crate) widths: Vec<W>,
+}
+
+impl<W: Width> Shape<W> {
+  /// Returns `true` if and only if the [`Shape`] contains no trees.
+  pub fn is_empty(&self) -> bool {
+    self.widths.is_empty()
+  }
+
+  /// Returns the number of nodes in the [`Shape`].
+  pub fn len(&self) -> usize {
+    self.widths.len()
+  }
+
+  /// Constructs a [`GroveBuf`] with this shape whose nodes hold `values` in
+  /// [`Preorder`][crate::Preorder]. Returns [`ShapeMismatch`] if `values` does
+  /// not yield exactly one value per node.
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let shape = grove_buf![[1, 2] => 3, 4].shape();
+  /// assert_eq!(
+  ///   shape.with_values("abcd".chars()),
+  ///   Ok(grove_buf![['a', 'b'] => 'c', 'd'])
+  /// );
+  /// assert_eq!(shape.with_values("abc".chars()), Err(ShapeMismatch));
+  /// ```
+  pub fn with_values<T>(
+    &self,
+    values: impl IntoIterator<Item = T>,
+  ) -> std::result::Result<GroveBuf<T, W>, ShapeMismatch> {
+    let mut values = values.into_iter();
+    let nodes = self
+      .widths
+      .iter()
+      .map(|&width| {
+        let value = values.next().ok_or(ShapeMismatch)?;
+        Ok(Node { value, width })
+      })
+      .collect::<std::result::Result<_, _>>()?;
+    match values.next() {
+      Some(_) => Err(ShapeMismatch),
+      None => Ok(GroveBuf {
+        nodes,
+        deleted: vec![],
+      }),
+    }
+  }
+}
+
+/// The error returned when an operation requires groves of identical shape
+/// but is given groves whose shapes differ.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ShapeMismatch;
+
+impl Display for ShapeMismatch {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    f.write_str("groves have different shapes")
+  }
+}
+
+impl std::error::Error for ShapeMismatch {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grove_buf;
+
+  #[test]
+  fn empty() {
+    let shape = GroveBuf::<i32>::new().shape();
+    assert!(shape.is_empty());
+    assert_eq!(shape.with_values(Vec::<i32>::new()), Ok(GroveBuf::new()));
+    assert_eq!(shape.with_values([1]), Err(ShapeMismatch));
+  }
+
+  #[test]
+  fn reuse() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    let shape = g.shape();
+    assert_eq!(shape.len(), 5);
+    assert_eq!(shape.with_values(g.nodes(crate::Preorder).copied()), Ok(g));
+    assert_eq!(
+      shape.with_values(["a", "b", "c", "d", "e"]),
+      Ok(grove_buf![["a", ["b"] => "c"] => "d", "e"])
+    );
+  }
+
+  #[test]
+  fn same_shape() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    assert!(g.same_shape(&grove_buf![["a", ["b"] => "c"] => "d", "e"]));
+    assert!(!g.same_shape(&grove_buf![["a", "b", "c"] => "d", "e"]));
+    assert!(!g.same_shape(&grove_buf![[1, [2] => 3] => 4]));
+    assert!(g[3].same_shape(&grove_buf![[1, [2] => 3] => 4][3]));
+    assert!(!g[3].same_shape(&g[2]));
+    assert_eq!(g.shape(), g.map(|n| n * 2).shape());
+  }
+}
@@ -0,0 +1,134 @@
+use crate::grove::Grove;
+use crate::node::Width;
+use crate::tree::Tree;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An owned handle to a single tree inside a shared [`Grove`]. Handles keep
+/// the underlying [`Grove`] alive, are cheap to clone, and dereference to the
+/// [`Tree`] they refer to, so subtrees can be handed out independently of the
+/// lifetime of any [`GroveBuf`][crate::GroveBuf].
+///
+/// # Example
+/// ```
+/// # use grove::*;
+/// # use std::sync::Arc;
+/// let g: Arc<Grove<i32>> = grove_buf![[1, [2] => 3] => 4, 5].into();
+/// let t = SharedTree::new(g, 3);
+/// let children: Vec<_> = t.children_rev().collect();
+/// assert_eq!(*children[0].root(), 3);
+/// assert_eq!(children[0].len(), 2);
+/// assert_eq!(*children[1].root(), 1);
+/// ```
+pub struct SharedTree<T, W = usize> {
+  grove: Arc<Grove<T, W>>,
+  root: usize,
+}
+
+impl<T, W: Width> SharedTree<T, W> {
+  /// Constructs a handle to the tree in `grove` whose root has the given
+  /// index.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `root` is out of bounds.
+  pub fn new(grove: Arc<Grove<T, W>>, root: usize) -> SharedTree<T, W> {
+    assert!(root < grove.len(), "root index out of bounds");
+    SharedTree { grove, root }
+  }
+
+  /// Returns the shared [`Grove`] containing the tree.
+  pub fn grove(&self) -> &Arc<Grove<T, W>> {
+    &self.grove
+  }
+
+  /// Returns the index of the root of `self` within its [`Grove`].
+  pub fn index(&self) -> usize {
+    self.root
+  }
+
+  /// Returns an iterator over handles to the children of the root, from right
+  /// to left. Analogous to [`Tree::children_rev`].
+  pub fn children_rev(
+    &self,
+  ) -> impl std::iter::Iterator<Item = SharedTree<T, W>> + '_ {
+    let start = self.root + 1 - self.len();
+    let mut position = self.root;
+    std::iter::from_fn(move || {
+      (position > start).then(|| {
+        let root = position - 1;
+        position -= self.grove.nodes[root].width();
+        SharedTree {
+          grove: self.grove.clone(),
+          root,
+        }
+      })
+    })
+  }
+}
+
+impl<T, W> Clone for SharedTree<T, W> {
+  fn clone(&self) -> SharedTree<T, W> {
+    SharedTree {
+      grove: self.grove.clone(),
+      root: self.root,
+    }
+  }
+}
+
+impl<T, W: Width> Deref for SharedTree<T, W> {
+  type Target = Tree<T, W>;
+
+  fn deref(&self) -> &Tree<T, W> {
+    &self.grove[self.root]
+  }
+}
+
+impl<T: Debug, W: Debug + Width> Debug for SharedTree<T, W> {
+  fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    (**self).fmt(f)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grove_buf;
+
+  #[test]
+  fn navigate() {
+    let g: Arc<Grove<i32>> =
+      grove_buf![[[1, 2] => 3, 4, [5, [6] => 7] => 8] => 9, [10] => 11].into();
+    let t = SharedTree::new(g.clone(), 8);
+    let roots: Vec<_> = t.children_rev().map(|c| *c.root()).collect();
+    assert_eq!(roots, vec![8, 4, 3]);
+
+    let grandchildren: Vec<_> = t
+      .children_rev()
+      .flat_map(|c| c.children_rev().collect::<Vec<_>>())
+      .map(|c| c.index())
+      .collect();
+    assert_eq!(grandchildren, vec![6, 4, 1, 0]);
+    assert!(SharedTree::new(g, 9).children_rev().next().is_none());
+  }
+
+  #[test]
+  fn outlives_source() {
+    let t = {
+      let g = grove_buf![[1, [2] => 3] => 4, 5];
+      SharedTree::new(g.into(), 2)
+    };
+    let clone = t.clone();
+    drop(t);
+    let handle = std::thread::spawn(move || (*clone.root(), clone.len()));
+    assert_eq!(handle.join().unwrap(), (3, 2));
+  }
+
+  #[test]
+  #[should_panic(expected = "root index out of bounds")]
+  fn out_of_bounds() {
+    SharedTree::new(grove_buf![1, 2].into(), 2);
+  }
+}
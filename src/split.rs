@@ -0,0 +1,515 @@
+use crate::grove_buf::GroveBuf;
+use crate::node::to_width;
+use crate::node::Width;
+use crate::scan;
+use crate::scan::Operands;
+use crate::shape::Shape;
+use crate::traversal::TraversalOrder;
+
+/// A sequence of trees with the same structure as a [`GroveBuf`], but which
+/// stores the widths of all subtrees and the values of all nodes in two
+/// separate allocations. No space is lost to padding between values and
+/// widths, and navigating the structure of the trees never touches the values.
+///
+/// Navigation is provided through the [`SplitGrove`] and [`SplitTree`] views,
+/// which mirror the read-only part of the API of [`Grove`][crate::Grove] and
+/// [`Tree`][crate::Tree]: `nodes` and `trees` traversals, `children_rev`, and
+/// the `scan_up`, `scan_down` and `evaluate` scans. Since the views are
+/// returned by value, subtrees are looked up with `tree` rather than by
+/// indexing, and [`Visitor`][crate::Visitor]s, whose hooks receive a
+/// [`Tree`][crate::Tree], are not supported. Values are modified through
+/// [`nodes_mut`][SplitGroveBuf::nodes_mut] and the [`SplitTreeMut`] view
+/// returned by [`tree_mut`][SplitGroveBuf::tree_mut]; there is no `trees_mut`,
+/// as mutable views of nested subtrees would overlap.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitGroveBuf<T, W = usize> {
+  pub(crate) shape: Shape<W>,
+  pub(crate) values: Vec<T>,
+}
+
+/// Constructs a [`SplitGroveBuf`] containing no trees.
+impl<T, W> Default for SplitGroveBuf<T, W> {
+  fn default() -> SplitGroveBuf<T, W> {
+    SplitGroveBuf {
+      shape: Shape { widths: vec![] },
+      values: vec![],
+    }
+  }
+}
+
+impl<T> SplitGroveBuf<T> {
+  /// Constructs a [`SplitGroveBuf`] containing no trees.
+  pub fn new() -> SplitGroveBuf<T> {
+    Default::default()
+  }
+}
+
+impl<T, W: Width> SplitGroveBuf<T, W> {
+  /// Returns `true` if and only if the [`SplitGroveBuf`] contains no trees.
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// Returns the number of nodes in the [`SplitGroveBuf`].
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns the [`Shape`] of the [`SplitGroveBuf`].
+  pub fn shape(&self) -> &Shape<W> {
+    &self.shape
+  }
+
+  /// Returns a [`SplitGrove`] view of `self`.
+  pub fn as_grove(&self) -> SplitGrove<'_, T, W> {
+    SplitGrove {
+      widths: &self.shape.widths,
+      values: &self.values,
+    }
+  }
+
+  /// Consumes `self` and returns its values in [`Preorder`][crate::Preorder],
+  /// such as to add them as a column of an
+  /// [`AttributeGrove`][crate::AttributeGrove].
+  pub fn into_values(self) -> Vec<T> {
+    self.values
+  }
+
+  /// Returns an iterator over references to the nodes in the grove according
+  /// to the specified traversal `order`.
+  pub fn nodes<Order: TraversalOrder>(
+    &self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &T> {
+    self.as_grove().nodes(order)
+  }
+
+  /// Returns an iterator over mutable references to the nodes in the grove
+  /// according to the specified traversal `order`.
+  pub fn nodes_mut<Order: TraversalOrder>(
+    &mut self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &mut T> {
+    order.traverse(self.values.iter_mut())
+  }
+
+  /// Returns an iterator over views of the trees in the grove according to the
+  /// specified traversal `order`. This includes all subtrees, not just the
+  /// top-level trees.
+  pub fn trees<Order: TraversalOrder>(
+    &self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = SplitTree<'_, T, W>> {
+    self.as_grove().trees(order)
+  }
+
+  /// Returns a view of the tree whose root has the given index.
+  pub fn tree(&self, index: usize) -> SplitTree<'_, T, W> {
+    self.as_grove().tree(index)
+  }
+
+  /// Computes a value for every node from its own value and the values
+  /// computed for its children. See [`Grove::scan_up`][crate::Grove::scan_up].
+  pub fn scan_up<U>(
+    &self,
+    f: impl FnMut(&T, &[U]) -> U,
+  ) -> SplitGroveBuf<U, W> {
+    self.as_grove().scan_up(f)
+  }
+
+  /// Computes a value for every node from its own value, the value computed
+  /// for its parent, and its ordinal among its siblings. See
+  /// [`Grove::scan_down`][crate::Grove::scan_down].
+  pub fn scan_down<U>(
+    &self,
+    f: impl FnMut(&T, Option<&U>, usize) -> U,
+  ) -> SplitGroveBuf<U, W> {
+    self.as_grove().scan_down(f)
+  }
+
+  /// Evaluates the grove as postfix code, returning one result per top-level
+  /// tree. See [`Grove::evaluate`][crate::Grove::evaluate].
+  pub fn evaluate<U>(&self, f: impl FnMut(&T, Operands<'_, U>) -> U) -> Vec<U> {
+    self.as_grove().evaluate(f)
+  }
+
+  /// Returns a mutable view of the tree whose root has the given index.
+  pub fn tree_mut(&mut self, index: usize) -> SplitTreeMut<'_, T, W> {
+    let start = index + 1 - self.shape.widths[index].to_usize();
+    SplitTreeMut {
+      widths: &self.shape.widths[start..=index],
+      values: &mut self.values[start..=index],
+    }
+  }
+
+  /// Appends a leaf with value `value` to the grove.
+  pub fn push(&mut self, value: T) {
+    self.shape.widths.push(to_width(1));
+    self.values.push(value);
+  }
+
+  /// Appends a node with value `value` that contains `children` child nodes.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of the new tree is not representable by `W`.
+  pub fn push_root(&mut self, value: T, children: usize) {
+    let mut element = self.values.len();
+    for _ in 0..children {
+      element -= self.shape.widths[element - 1].to_usize();
+    }
+    unsafe {
+      self.push_unchecked(value, element);
+    }
+  }
+
+  /// Appends a node with value `value` that contains all elements at index
+  /// `position` and larger in its subtree.
+  ///
+  /// # Safety
+  ///
+  /// `position` must be at most `self.len()` and must be the index of the
+  /// first node of a tree in the [`SplitGroveBuf`] (or equal to `self.len()`).
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width of the new tree is not representable by `W`.
+  pub unsafe fn push_unchecked(&mut self, value: T, position: usize) {
+    self.shape.widths.push(to_width(self.values.len() - position + 1));
+    self.values.push(value);
+  }
+}
+
+impl<T, W: Width> From<GroveBuf<T, W>> for SplitGroveBuf<T, W> {
+  fn from(g: GroveBuf<T, W>) -> SplitGroveBuf<T, W> {
+    let (shape, values) = g.into_parts();
+    SplitGroveBuf { shape, values }
+  }
+}
+
+impl<T, W: Width> From<SplitGroveBuf<T, W>> for GroveBuf<T, W> {
+  fn from(g: SplitGroveBuf<T, W>) -> GroveBuf<T, W> {
+    g.shape.with_values(g.values).unwrap()
+  }
+}
+
+/// A view of a collection of consecutive trees inside a [`SplitGroveBuf`].
+/// Analogous to [`Grove`][crate::Grove].
+#[derive(Debug)]
+pub struct SplitGrove<'a, T, W = usize> {
+  pub(crate) widths: &'a [W],
+  pub(crate) values: &'a [T],
+}
+
+impl<T, W> Clone for SplitGrove<'_, T, W> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T, W> Copy for SplitGrove<'_, T, W> {}
+
+impl<'a, T, W: Width> SplitGrove<'a, T, W> {
+  /// Returns `true` if and only if the [`SplitGrove`] contains no trees.
+  pub fn is_empty(self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// Returns the number of nodes in the [`SplitGrove`].
+  pub fn len(self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns an iterator traversing through references to nodes in the
+  /// [`SplitGrove`] according to the prescribed `TraversalOrder`.
+  pub fn nodes<Order: TraversalOrder>(
+    self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = &'a T> {
+    order.traverse(self.values.iter())
+  }
+
+  /// Returns an iterator traversing through views of the trees in the
+  /// [`SplitGrove`] according to the prescribed `TraversalOrder`.
+  pub fn trees<Order: TraversalOrder>(
+    self,
+    order: Order,
+  ) -> impl std::iter::Iterator<Item = SplitTree<'a, T, W>> {
+    order.traverse(0..self.values.len()).map(move |i| self.tree(i))
+  }
+
+  /// Returns a view of the tree whose root has the given index.
+  pub fn tree(self, index: usize) -> SplitTree<'a, T, W> {
+    let start = index + 1 - self.widths[index].to_usize();
+    SplitTree {
+      widths: &self.widths[start..=index],
+      values: &self.values[start..=index],
+    }
+  }
+
+  /// Analogous to [`Grove::scan_up`][crate::Grove::scan_up].
+  ///
+  /// # Example
+  /// ```
+  /// # use grove::*;
+  /// let g: SplitGroveBuf<i32> = grove_buf![[1, [2, 3] => 4] => 5, 6].into();
+  /// let sums = g.scan_up(|n, children| n + children.iter().sum::<i32>());
+  /// assert_eq!(sums, grove_buf![[1, [2, 3] => 9] => 15, 6].into());
+  /// ```
+  pub fn scan_up<U>(
+    self,
+    mut f: impl FnMut(&T, &[U]) -> U,
+  ) -> SplitGroveBuf<U, W> {
+    let values = scan::scan_up(
+      self.values.len(),
+      |i| self.widths[i].to_usize(),
+      |i, children| f(&self.values[i], children),
+    );
+    self.with_values(values)
+  }
+
+  /// Analogous to [`Grove::scan_down`][crate::Grove::scan_down].
+  pub fn scan_down<U>(
+    self,
+    mut f: impl FnMut(&T, Option<&U>, usize) -> U,
+  ) -> SplitGroveBuf<U, W> {
+    let values = scan::scan_down(
+      self.values.len(),
+      |i| self.widths[i].to_usize(),
+      |i, parent, ordinal| f(&self.values[i], parent, ordinal),
+    );
+    self.with_values(values)
+  }
+
+  /// Analogous to [`Grove::evaluate`][crate::Grove::evaluate].
+  pub fn evaluate<U>(
+    self,
+    mut f: impl FnMut(&T, Operands<'_, U>) -> U,
+  ) -> Vec<U> {
+    scan::evaluate(
+      self.values.len(),
+      |i| self.widths[i].to_usize(),
+      |i, operands| f(&self.values[i], operands),
+    )
+  }
+
+  // Returns a `SplitGroveBuf` with the same shape as `self` holding `values`,
+  // which must have one value per node.
+  fn with_values<U>(self, values: Vec<U>) -> SplitGroveBuf<U, W> {
+    SplitGroveBuf {
+      shape: Shape {
+        widths: self.widths.to_vec(),
+      },
+      values,
+    }
+  }
+}
+
+/// A view of a single tree inside a [`SplitGroveBuf`]. Analogous to
+/// [`Tree`][crate::Tree].
+#[derive(Debug)]
+pub struct SplitTree<'a, T, W = usize> {
+  pub(crate) widths: &'a [W],
+  pub(crate) values: &'a [T],
+}
+
+impl<T, W> Clone for SplitTree<'_, T, W> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T, W> Copy for SplitTree<'_, T, W> {}
+
+impl<'a, T, W: Width> SplitTree<'a, T, W> {
+  /// Returns a reference to the value held at the root of the tree.
+  pub fn root(self) -> &'a T {
+    self.values.last().unwrap()
+  }
+
+  /// Returns the number of nodes contained in the tree (including the root).
+  #[allow(clippy::len_without_is_empty)]
+  pub fn len(self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns a [`SplitGrove`] view consisting of just this tree.
+  pub fn as_grove(self) -> SplitGrove<'a, T, W> {
+    SplitGrove {
+      widths: self.widths,
+      values: self.values,
+    }
+  }
+
+  /// Returns a view of the subtree whose root has the given index within this
+  /// tree.
+  pub fn tree(self, index: usize) -> SplitTree<'a, T, W> {
+    self.as_grove().tree(index)
+  }
+
+  /// Returns an iterator over views of the maximal proper subtrees in reverse
+  /// order.
+  pub fn children_rev(
+    self,
+  ) -> impl std::iter::Iterator<Item = SplitTree<'a, T, W>> {
+    let mut end = self.values.len() - 1;
+    std::iter::from_fn(move || {
+      if end == 0 {
+        return None;
+      }
+      let tree = self.tree(end - 1);
+      end -= tree.len();
+      Some(tree)
+    })
+  }
+}
+
+/// A mutable view of a single tree inside a [`SplitGroveBuf`]. The values may
+/// be modified, but the structure may not.
+#[derive(Debug)]
+pub struct SplitTreeMut<'a, T, W = usize> {
+  pub(crate) widths: &'a [W],
+  pub(crate) values: &'a mut [T],
+}
+
+impl<T, W: Width> SplitTreeMut<'_, T, W> {
+  /// Returns a reference to the value held at the root of the tree.
+  pub fn root(&self) -> &T {
+    self.values.last().unwrap()
+  }
+
+  /// Returns a mutable reference to the value held at the root of the tree.
+  pub fn root_mut(&mut self) -> &mut T {
+    self.values.last_mut().unwrap()
+  }
+
+  /// Returns the number of nodes contained in the tree (including the root).
+  #[allow(clippy::len_without_is_empty)]
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns an immutable view of this tree.
+  pub fn as_tree(&self) -> SplitTree<'_, T, W> {
+    SplitTree {
+      widths: self.widths,
+      values: self.values,
+    }
+  }
+
+  /// Analogous to [`SplitTree::children_rev`] but iterates through mutable
+  /// views.
+  pub fn children_rev_mut(
+    &mut self,
+  ) -> impl std::iter::Iterator<Item = SplitTreeMut<'_, T, W>> {
+    let len = self.values.len() - 1;
+    let mut widths = &self.widths[..len];
+    let mut values = &mut self.values[..len];
+    std::iter::from_fn(move || {
+      let width = widths.last()?.to_usize();
+      let (front_widths, back_widths) = widths.split_at(widths.len() - width);
+      let (front_values, back_values) =
+        std::mem::take(&mut values).split_at_mut(front_widths.len());
+      widths = front_widths;
+      values = front_values;
+      Some(SplitTreeMut {
+        widths: back_widths,
+        values: back_values,
+      })
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grove_buf;
+  use crate::traversal::Preorder;
+  use crate::traversal::ReversePostorder;
+
+  fn example() -> SplitGroveBuf<i32> {
+    grove_buf![[1, 2] => 3, 4, [5, [6] => 7] => 8].into()
+  }
+
+  #[test]
+  fn empty() {
+    let g = SplitGroveBuf::<i32>::new();
+    assert!(g.is_empty());
+    assert_eq!(g.len(), 0);
+    assert_eq!(g.nodes(Preorder).count(), 0);
+  }
+
+  #[test]
+  fn push() {
+    let mut g = SplitGroveBuf::new();
+    g.push(1);
+    g.push(2);
+    g.push_root(3, 2);
+    g.push(4);
+    g.push(5);
+    g.push(6);
+    g.push_root(7, 1);
+    g.push_root(8, 2);
+    assert_eq!(g, example());
+    assert_eq!(
+      GroveBuf::from(g),
+      grove_buf![[1, 2] => 3, 4, [5, [6] => 7] => 8]
+    );
+  }
+
+  #[test]
+  fn nodes() {
+    let mut g = example();
+    let v: Vec<_> = g.nodes(ReversePostorder).copied().collect();
+    assert_eq!(v, vec![8, 7, 6, 5, 4, 3, 2, 1]);
+    for n in g.nodes_mut(Preorder) {
+      *n *= 10;
+    }
+    let v: Vec<_> = g.nodes(Preorder).copied().collect();
+    assert_eq!(v, vec![10, 20, 30, 40, 50, 60, 70, 80]);
+  }
+
+  #[test]
+  fn trees() {
+    let g = example();
+    let v: Vec<_> = g.trees(Preorder).map(|t| (t.len(), *t.root())).collect();
+    assert_eq!(
+      v,
+      vec![(1, 1), (1, 2), (3, 3), (1, 4), (1, 5), (1, 6), (2, 7), (4, 8)]
+    );
+  }
+
+  #[test]
+  fn children_rev() {
+    let g = example();
+    let v: Vec<_> = g.tree(7).children_rev().map(|t| *t.root()).collect();
+    assert_eq!(v, vec![7, 5]);
+    assert_eq!(g.tree(7).tree(2).len(), 2);
+    assert_eq!(g.tree(0).children_rev().count(), 0);
+  }
+
+  #[test]
+  fn scans() {
+    let g = example();
+    let sizes = g.scan_up(|_, children| 1 + children.iter().sum::<usize>());
+    assert_eq!(sizes.shape(), g.shape());
+    assert_eq!(sizes.values, vec![1, 1, 3, 1, 1, 1, 2, 4]);
+    let depths =
+      g.scan_down(|_, parent: Option<&i32>, _| parent.map_or(0, |d| d + 1));
+    assert_eq!(depths.values, vec![1, 1, 0, 0, 1, 2, 1, 0]);
+    let v = g.evaluate(|n, operands| n * 10 + operands.len() as i32);
+    assert_eq!(v, vec![32, 40, 82]);
+  }
+
+  #[test]
+  fn children_rev_mut() {
+    let mut g = example();
+    let mut t = g.tree_mut(7);
+    for mut child in t.children_rev_mut() {
+      *child.root_mut() += 100;
+    }
+    *t.root_mut() += 100;
+    let v: Vec<_> = g.nodes(Preorder).copied().collect();
+    assert_eq!(v, vec![1, 2, 3, 4, 105, 6, 107, 108]);
+  }
+}
@@ -1,44 +1,105 @@
 use crate::internal::get_tree;
 use crate::internal::get_tree_mut;
 use crate::node::Node;
+use crate::node::Width;
 use crate::tree::Tree;
 
 pub(crate) trait TraversalOrder: Sized {
-  fn iter<'a, T: 'a>(
+  fn traverse<I: DoubleEndedIterator>(
     self,
-    nodes: &'a [Node<T>],
-  ) -> impl Iterator<Item = &'a Node<T>>;
+    iter: I,
+  ) -> impl Iterator<Item = I::Item>;
 
-  fn iter_mut<'a, T: 'a>(
+  fn node_iter<'a, T: 'a, W: 'a>(
     self,
-    nodes: &'a mut [Node<T>],
-  ) -> impl Iterator<Item = &'a mut Node<T>>;
+    nodes: &'a [Node<T, W>],
+  ) -> impl std::iter::Iterator<Item = &'a T> {
+    self.traverse(nodes.iter()).map(|node| &node.value)
+  }
+  fn tree_iter<'a, T: 'a, W: Width + 'a>(
+    self,
+    nodes: &'a [Node<T, W>],
+  ) -> impl std::iter::Iterator<Item = &'a Tree<T, W>> {
+    self.traverse(nodes.iter()).map(get_tree)
+  }
 
-  fn node_iter<'a, T: 'a>(
+  fn node_iter_mut<'a, T: 'a, W: 'a>(
     self,
-    nodes: &'a [Node<T>],
+    nodes: &'a mut [Node<T, W>],
+  ) -> impl std::iter::Iterator<Item = &'a mut T> {
+    self.traverse(nodes.iter_mut()).map(|node| &mut node.value)
+  }
+
+  fn tree_iter_mut<'a, T: 'a, W: Width + 'a>(
+    self,
+    nodes: &'a mut [Node<T, W>],
+  ) -> impl std::iter::Iterator<Item = &'a mut Tree<T, W>> {
+    self.traverse(nodes.iter_mut()).map(get_tree_mut)
+  }
+
+  // The `live_` iterators skip every node `i` for which `deleted[i]` is `true`.
+  // `deleted` must hold one entry per node.
+
+  fn live_node_iter<'a, T: 'a, W: 'a>(
+    self,
+    nodes: &'a [Node<T, W>],
+    deleted: &'a [bool],
   ) -> impl std::iter::Iterator<Item = &'a T> {
-    self.iter(nodes).map(|node| &node.value)
+    self
+      .traverse(nodes.iter().zip(deleted))
+      .filter(|(_, &deleted)| !deleted)
+      .map(|(node, _)| &node.value)
   }
-  fn tree_iter<'a, T: 'a>(
+
+  fn live_tree_iter<'a, T: 'a, W: Width + 'a>(
     self,
-    nodes: &'a [Node<T>],
-  ) -> impl std::iter::Iterator<Item = &'a Tree<T>> {
-    self.iter(nodes).map(get_tree)
+    nodes: &'a [Node<T, W>],
+    deleted: &'a [bool],
+  ) -> impl std::iter::Iterator<Item = &'a Tree<T, W>> {
+    self
+      .traverse(nodes.iter().zip(deleted))
+      .filter(|(_, &deleted)| !deleted)
+      .map(|(node, _)| get_tree(node))
   }
 
-  fn node_iter_mut<'a, T: 'a>(
+  fn live_node_iter_mut<'a, T: 'a, W: 'a>(
     self,
-    nodes: &'a mut [Node<T>],
+    nodes: &'a mut [Node<T, W>],
+    deleted: &'a [bool],
   ) -> impl std::iter::Iterator<Item = &'a mut T> {
-    self.iter_mut(nodes).map(|node| &mut node.value)
+    self
+      .traverse(nodes.iter_mut().zip(deleted))
+      .filter(|(_, &deleted)| !deleted)
+      .map(|(node, _)| &mut node.value)
   }
 
-  fn tree_iter_mut<'a, T: 'a>(
+  fn live_tree_iter_mut<'a, T: 'a, W: Width + 'a>(
     self,
-    nodes: &'a mut [Node<T>],
-  ) -> impl std::iter::Iterator<Item = &'a mut Tree<T>> {
-    self.iter_mut(nodes).map(get_tree_mut)
+    nodes: &'a mut [Node<T, W>],
+    deleted: &'a [bool],
+  ) -> impl std::iter::Iterator<Item = &'a mut Tree<T, W>> {
+    self
+      .traverse(nodes.iter_mut().zip(deleted))
+      .filter(|(_, &deleted)| !deleted)
+      .map(|(node, _)| get_tree_mut(node))
+  }
+}
+
+/// An iterator yielding the items of either of two iterators, so that a
+/// function can choose between them at run time.
+pub(crate) enum Either<A, B> {
+  Left(A),
+  Right(B),
+}
+
+impl<A: Iterator, B: Iterator<Item = A::Item>> Iterator for Either<A, B> {
+  type Item = A::Item;
+
+  fn next(&mut self) -> Option<A::Item> {
+    match self {
+      Either::Left(a) => a.next(),
+      Either::Right(b) => b.next(),
+    }
   }
 }
 
@@ -47,18 +108,11 @@ pub(crate) trait TraversalOrder: Sized {
 /// * Each node's children are visited before the node itself.
 pub struct Preorder;
 impl TraversalOrder for Preorder {
-  fn iter<'a, T: 'a>(
+  fn traverse<I: DoubleEndedIterator>(
     self,
-    nodes: &'a [Node<T>],
-  ) -> impl Iterator<Item = &'a Node<T>> {
-    nodes.iter()
-  }
-
-  fn iter_mut<'a, T: 'a>(
-    self,
-    nodes: &'a mut [Node<T>],
-  ) -> impl Iterator<Item = &'a mut Node<T>> {
-    nodes.iter_mut()
+    iter: I,
+  ) -> impl Iterator<Item = I::Item> {
+    iter
   }
 }
 
@@ -67,17 +121,10 @@ impl TraversalOrder for Preorder {
 /// * Each node is visited before its children.
 pub struct ReversePostorder;
 impl TraversalOrder for ReversePostorder {
-  fn iter<'a, T: 'a>(
-    self,
-    nodes: &'a [Node<T>],
-  ) -> impl Iterator<Item = &'a Node<T>> {
-    nodes.iter().rev()
-  }
-
-  fn iter_mut<'a, T: 'a>(
+  fn traverse<I: DoubleEndedIterator>(
     self,
-    nodes: &'a mut [Node<T>],
-  ) -> impl Iterator<Item = &'a mut Node<T>> {
-    nodes.iter_mut().rev()
+    iter: I,
+  ) -> impl Iterator<Item = I::Item> {
+    iter.rev()
   }
 }
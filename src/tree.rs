@@ -1,44 +1,57 @@
+use crate::grove::Grove;
+use crate::internal::as_grove_unchecked;
 use crate::internal::as_tree_mut_unchecked;
+use crate::internal::arc_as_tree_unchecked;
 use crate::internal::as_tree_unchecked;
+use crate::internal::box_as_tree_unchecked;
 use crate::internal::get_tree;
 use crate::internal::get_tree_mut;
+use crate::internal::rc_as_tree_unchecked;
 use crate::node::Node;
+use crate::node::Width;
+use crate::visitor;
+use crate::visitor::Visitor;
+use crate::visitor::VisitorMut;
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// An unsized type referencing a a single tree inside a
 /// [`GroveBuf`][crate::GroveBuf].
 #[repr(transparent)]
-#[derive(Debug, Eq)]
-pub struct Tree<T> {
-  pub(crate) nodes: [Node<T>],
+#[derive(Eq)]
+pub struct Tree<T, W = usize> {
+  pub(crate) nodes: [Node<T, W>],
 }
 
-impl<T, U: PartialEq<T>> PartialEq<Tree<T>> for Tree<U> {
-  fn eq(&self, t: &Tree<T>) -> bool {
+impl<T, U: PartialEq<T>, W: PartialEq> PartialEq<Tree<T, W>>
+  for Tree<U, W>
+{
+  fn eq(&self, t: &Tree<T, W>) -> bool {
     self.nodes == t.nodes
   }
 }
 
-struct ChildIter<'a, T>(&'a [Node<T>]);
+struct ChildIter<'a, T, W>(&'a [Node<T, W>]);
 
-impl<'a, T> std::iter::Iterator for ChildIter<'a, T> {
-  type Item = &'a Tree<T>;
+impl<'a, T, W: Width> std::iter::Iterator for ChildIter<'a, T, W> {
+  type Item = &'a Tree<T, W>;
 
   fn next(&mut self) -> Option<Self::Item> {
     let last = self.0.last()?;
-    let (new_nodes, tree_nodes) = self.0.split_at(self.0.len() - last.width);
+    let (new_nodes, tree_nodes) = self.0.split_at(self.0.len() - last.width());
     self.0 = new_nodes;
     Some(unsafe { as_tree_unchecked(tree_nodes) })
   }
 }
 
-struct ChildIterMut<'a, T>(&'a mut [Node<T>]);
+struct ChildIterMut<'a, T, W>(&'a mut [Node<T, W>]);
 
-impl<'a, T> std::iter::Iterator for ChildIterMut<'a, T> {
-  type Item = &'a mut Tree<T>;
+impl<'a, T, W: Width> std::iter::Iterator for ChildIterMut<'a, T, W> {
+  type Item = &'a mut Tree<T, W>;
 
   fn next(&mut self) -> Option<Self::Item> {
     let last = self.0.last_mut()?;
-    let width = last.width;
+    let width = last.width();
     let (front, back) =
       std::mem::take(&mut self.0).split_at_mut(self.0.len() - width);
     self.0 = front;
@@ -46,7 +59,7 @@ impl<'a, T> std::iter::Iterator for ChildIterMut<'a, T> {
   }
 }
 
-impl<T> Tree<T> {
+impl<T, W: Width> Tree<T, W> {
   /// Returns a reference to the value held at the root of the tree.
   pub fn root(&self) -> &T {
     &self.nodes.last().unwrap().value
@@ -58,6 +71,7 @@ impl<T> Tree<T> {
   }
 
   /// Retruns the number of nodes contained in the tree (including the root).
+  #[allow(clippy::len_without_is_empty)]
   pub fn len(&self) -> usize {
     self.nodes.len()
   }
@@ -79,28 +93,78 @@ impl<T> Tree<T> {
   ///               ]
   /// );
   /// ```
-  pub fn children_rev(&self) -> impl std::iter::Iterator<Item = &Tree<T>> {
+  pub fn children_rev(
+    &self,
+  ) -> impl std::iter::Iterator<Item = &Tree<T, W>> {
     ChildIter(&self.nodes[..self.nodes.len() - 1])
   }
 
   /// Analogous to [`children_rev`][Tree::children_rev] but iterates through mutable references.
   pub fn children_rev_mut(
     &mut self,
-  ) -> impl std::iter::Iterator<Item = &mut Tree<T>> {
+  ) -> impl std::iter::Iterator<Item = &mut Tree<T, W>> {
     let len = self.nodes.len() - 1;
     ChildIterMut(&mut self.nodes[..len])
   }
+
+  /// Returns a [`Grove`] view consisting of just this tree.
+  pub fn as_grove(&self) -> &Grove<T, W> {
+    unsafe { as_grove_unchecked(&self.nodes) }
+  }
+
+  /// Returns `true` if and only if `self` and `other` have the same shape,
+  /// regardless of the values they hold.
+  pub fn same_shape<U>(&self, other: &Tree<U, W>) -> bool {
+    self.nodes.len() == other.nodes.len()
+      && self.nodes.iter().zip(&other.nodes).all(|(l, r)| l.width == r.width)
+  }
+
+  /// Traverses the tree with `visitor`, invoking [`Visitor::enter`] on each
+  /// subtree before its children are visited and [`Visitor::leave`] after.
+  pub fn visit<V: Visitor<T, W> + ?Sized>(&self, visitor: &mut V) {
+    visitor::visit(&self.nodes, |_| true, visitor)
+  }
+
+  /// Analogous to [`visit`][Tree::visit] but invokes the hooks of a
+  /// [`VisitorMut`] with mutable references.
+  pub fn visit_mut<V: VisitorMut<T, W> + ?Sized>(&mut self, visitor: &mut V) {
+    visitor::visit_mut(&mut self.nodes, |_| true, visitor)
+  }
+}
+
+impl<T: Clone, W: Width> From<&Tree<T, W>> for Box<Tree<T, W>> {
+  fn from(t: &Tree<T, W>) -> Box<Tree<T, W>> {
+    unsafe { box_as_tree_unchecked(t.nodes.into()) }
+  }
+}
+
+impl<T: Clone, W: Width> From<&Tree<T, W>> for Rc<Tree<T, W>> {
+  fn from(t: &Tree<T, W>) -> Rc<Tree<T, W>> {
+    unsafe { rc_as_tree_unchecked(t.nodes.into()) }
+  }
+}
+
+impl<T: Clone, W: Width> From<&Tree<T, W>> for Arc<Tree<T, W>> {
+  fn from(t: &Tree<T, W>) -> Arc<Tree<T, W>> {
+    unsafe { arc_as_tree_unchecked(t.nodes.into()) }
+  }
+}
+
+impl<T: Clone, W: Width> Clone for Box<Tree<T, W>> {
+  fn clone(&self) -> Box<Tree<T, W>> {
+    self.as_ref().into()
+  }
 }
 
-impl<T> std::ops::Index<usize> for Tree<T> {
-  type Output = Tree<T>;
+impl<T, W: Width> std::ops::Index<usize> for Tree<T, W> {
+  type Output = Tree<T, W>;
 
   fn index(&self, index: usize) -> &Self::Output {
     get_tree(&self.nodes[index])
   }
 }
 
-impl<T> std::ops::IndexMut<usize> for Tree<T> {
+impl<T, W: Width> std::ops::IndexMut<usize> for Tree<T, W> {
   fn index_mut(&mut self, index: usize) -> &mut Self::Output {
     get_tree_mut(&mut self.nodes[index])
   }
@@ -0,0 +1,158 @@
+use crate::internal::get_tree;
+use crate::internal::get_tree_mut;
+use crate::node::Node;
+use crate::node::Width;
+use crate::tree::Tree;
+
+/// A type whose hooks are invoked while traversing a [`Grove`][crate::Grove].
+/// For each tree, [`enter`][Visitor::enter] is invoked before any of its
+/// subtrees are visited and [`leave`][Visitor::leave] is invoked after all of
+/// them have been visited. Sibling subtrees are visited in left-to-right order.
+pub trait Visitor<T, W = usize> {
+  /// Invoked on `tree` before any of its proper subtrees are visited.
+  fn enter(&mut self, _tree: &Tree<T, W>) {}
+
+  /// Invoked on `tree` after all of its proper subtrees have been visited.
+  fn leave(&mut self, _tree: &Tree<T, W>) {}
+}
+
+/// Analogous to [`Visitor`], but the hooks are invoked with mutable references.
+pub trait VisitorMut<T, W = usize> {
+  /// Invoked on `tree` before any of its proper subtrees are visited.
+  fn enter(&mut self, _tree: &mut Tree<T, W>) {}
+
+  /// Invoked on `tree` after all of its proper subtrees have been visited.
+  fn leave(&mut self, _tree: &mut Tree<T, W>) {}
+}
+
+enum Step {
+  Enter(usize),
+  Leave(usize),
+}
+
+// Schedules each tree in `nodes[start..end]` whose root `i` satisfies
+// `live(i)` to be entered, such that the leftmost tree is at the top of
+// `stack`.
+fn schedule<T, W: Width>(
+  nodes: &[Node<T, W>],
+  live: &impl Fn(usize) -> bool,
+  start: usize,
+  mut end: usize,
+  stack: &mut Vec<Step>,
+) {
+  while end > start {
+    if live(end - 1) {
+      stack.push(Step::Enter(end - 1));
+    }
+    end -= nodes[end - 1].width();
+  }
+}
+
+// Visits the trees in `nodes`, skipping every subtree whose root `i` does not
+// satisfy `live(i)`.
+pub(crate) fn visit<T, W: Width, V: Visitor<T, W> + ?Sized>(
+  nodes: &[Node<T, W>],
+  live: impl Fn(usize) -> bool,
+  visitor: &mut V,
+) {
+  let mut stack = vec![];
+  schedule(nodes, &live, 0, nodes.len(), &mut stack);
+  while let Some(step) = stack.pop() {
+    match step {
+      Step::Enter(root) => {
+        visitor.enter(get_tree(&nodes[root]));
+        stack.push(Step::Leave(root));
+        let start = root + 1 - nodes[root].width();
+        schedule(nodes, &live, start, root, &mut stack);
+      }
+      Step::Leave(root) => visitor.leave(get_tree(&nodes[root])),
+    }
+  }
+}
+
+pub(crate) fn visit_mut<T, W: Width, V: VisitorMut<T, W> + ?Sized>(
+  nodes: &mut [Node<T, W>],
+  live: impl Fn(usize) -> bool,
+  visitor: &mut V,
+) {
+  let mut stack = vec![];
+  schedule(nodes, &live, 0, nodes.len(), &mut stack);
+  while let Some(step) = stack.pop() {
+    match step {
+      Step::Enter(root) => {
+        visitor.enter(get_tree_mut(&mut nodes[root]));
+        stack.push(Step::Leave(root));
+        let start = root + 1 - nodes[root].width();
+        schedule(nodes, &live, start, root, &mut stack);
+      }
+      Step::Leave(root) => visitor.leave(get_tree_mut(&mut nodes[root])),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::grove_buf;
+
+  #[derive(Default)]
+  struct Recorder(Vec<String>);
+
+  impl Visitor<i32> for Recorder {
+    fn enter(&mut self, tree: &Tree<i32>) {
+      self.0.push(format!("+{}", tree.root()));
+    }
+
+    fn leave(&mut self, tree: &Tree<i32>) {
+      self.0.push(format!("-{}", tree.root()));
+    }
+  }
+
+  #[test]
+  fn empty() {
+    let g = crate::GroveBuf::<i32>::new();
+    let mut r = Recorder::default();
+    g.visit(&mut r);
+    assert!(r.0.is_empty());
+  }
+
+  #[test]
+  fn enter_and_leave() {
+    let g = grove_buf![[1, [2] => 3] => 4, 5];
+    let mut r = Recorder::default();
+    g.visit(&mut r);
+    assert_eq!(
+      r.0,
+      vec!["+4", "+1", "-1", "+3", "+2", "-2", "-3", "-4", "+5", "-5"]
+    );
+  }
+
+  #[test]
+  fn deep_tree() {
+    let g = crate::internal::deep_tree();
+    let mut r = Recorder::default();
+    g.visit(&mut r);
+    assert_eq!(r.0.len(), 200_000);
+    assert_eq!(r.0[0], "+99999");
+    assert_eq!(r.0[199_999], "-99999");
+  }
+
+  #[test]
+  fn visit_mut() {
+    struct Depth(i32);
+    impl VisitorMut<i32> for Depth {
+      fn enter(&mut self, tree: &mut Tree<i32>) {
+        *tree.root_mut() = self.0;
+        self.0 += 1;
+      }
+
+      fn leave(&mut self, _: &mut Tree<i32>) {
+        self.0 -= 1;
+      }
+    }
+
+    let mut g = grove_buf![[1, [2] => 3] => 4, 5];
+    g.visit_mut(&mut Depth(0));
+    assert_eq!(g, grove_buf![[1, [2] => 1] => 0, 0]);
+  }
+}